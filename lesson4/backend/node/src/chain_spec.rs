@@ -1,6 +1,6 @@
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY,
+	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, KittiesConfig, Signature,
+	SudoConfig, SystemConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -36,6 +36,21 @@ pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
 	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
 }
 
+/// Generate a male and a female genesis kitty for each owner, so they can breed right away.
+pub fn kitties_for(owners: &[AccountId]) -> Vec<(AccountId, [u8; 16])> {
+	owners
+		.iter()
+		.enumerate()
+		.flat_map(|(i, owner)| {
+			// The lowest bit of the first byte decides the gender.
+			let seed = (i as u8).wrapping_mul(16);
+			let male = [seed.wrapping_add(2); 16];
+			let female = [seed.wrapping_add(3); 16];
+			vec![(owner.clone(), male), (owner.clone(), female)]
+		})
+		.collect()
+}
+
 pub fn development_config() -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
 
@@ -59,6 +74,11 @@ pub fn development_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 				],
+				// Genesis kitties
+				kitties_for(&[
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+				]),
 				true,
 			)
 		},
@@ -107,6 +127,12 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
+				// Genesis kitties
+				kitties_for(&[
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+					get_account_id_from_seed::<sr25519::Public>("Charlie"),
+				]),
 				true,
 			)
 		},
@@ -130,6 +156,7 @@ fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	initial_kitties: Vec<(AccountId, [u8; 16])>,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
@@ -152,5 +179,9 @@ fn testnet_genesis(
 			key: Some(root_key),
 		},
 		transaction_payment: Default::default(),
+		kitties: KittiesConfig {
+			// Genesis kitties reserve the kitty price, so their owners must be endowed.
+			kitties: initial_kitties,
+		},
 	}
 }
@@ -0,0 +1,32 @@
+//! kitties 的 runtime api，供 rpc 调用
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+pub use pallet_kitties::KittyInfo;
+
+sp_api::decl_runtime_apis! {
+	pub trait KittiesApi<AccountId, KittyIndex, BlockNumber> where
+		AccountId: Codec,
+		KittyIndex: Codec,
+		BlockNumber: Codec,
+	{
+		//分页查询账户名下kitty的id，从 start_after 之后开始，最多返回 limit 个
+		fn kitties_of(
+			account: AccountId,
+			start_after: Option<KittyIndex>,
+			limit: u32,
+		) -> Vec<KittyIndex>;
+
+		//账户拥有的kitty数量
+		fn owned_count(account: AccountId) -> u32;
+
+		//kitty的拥有者和详细信息
+		fn kitty_details(kitty_id: KittyIndex) -> Option<KittyInfo<AccountId, KittyIndex, BlockNumber>>;
+
+		//当前存在的kitty总量
+		fn total_supply() -> KittyIndex;
+	}
+}
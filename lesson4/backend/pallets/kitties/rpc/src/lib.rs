@@ -0,0 +1,125 @@
+//! kitties 的 rpc 方法：kitties_kittiesOf、kitties_ownedCount、kitties_kittyDetails 和 kitties_totalSupply
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpsee::{
+	core::{async_trait, Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_kitties_rpc_runtime_api::{KittiesApi as KittiesRuntimeApi, KittyInfo};
+
+#[rpc(client, server)]
+pub trait KittiesApi<BlockHash, AccountId, KittyIndex, BlockNumber> {
+	//分页查询账户名下kitty的id，下一页的 start_after 传入这一页的最后一个id
+	#[method(name = "kitties_kittiesOf")]
+	fn kitties_of(
+		&self,
+		account: AccountId,
+		start_after: Option<KittyIndex>,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<KittyIndex>>;
+
+	//账户拥有的kitty数量
+	#[method(name = "kitties_ownedCount")]
+	fn owned_count(&self, account: AccountId, at: Option<BlockHash>) -> RpcResult<u32>;
+
+	//kitty的拥有者和详细信息
+	#[method(name = "kitties_kittyDetails")]
+	fn kitty_details(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<KittyInfo<AccountId, KittyIndex, BlockNumber>>>;
+
+	//当前存在的kitty总量
+	#[method(name = "kitties_totalSupply")]
+	fn total_supply(&self, at: Option<BlockHash>) -> RpcResult<KittyIndex>;
+}
+
+/// Provides RPC methods to query the kitties pallet.
+pub struct Kitties<C, Block> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block> Kitties<C, Block> {
+	/// Creates a new instance of the Kitties RPC helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+//调用runtime出错时返回的错误码
+const RUNTIME_ERROR: i32 = 1;
+
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		RUNTIME_ERROR,
+		"Unable to query kitties from runtime",
+		Some(format!("{:?}", err)),
+	))
+	.into()
+}
+
+#[async_trait]
+impl<C, Block, AccountId, KittyIndex, BlockNumber>
+	KittiesApiServer<<Block as BlockT>::Hash, AccountId, KittyIndex, BlockNumber> for Kitties<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: KittiesRuntimeApi<Block, AccountId, KittyIndex, BlockNumber>,
+	AccountId: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	KittyIndex: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	BlockNumber: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+	fn kitties_of(
+		&self,
+		account: AccountId,
+		start_after: Option<KittyIndex>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<KittyIndex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.kitties_of(&at, account, start_after, limit)
+			.map_err(runtime_error_into_rpc_err)
+	}
+
+	fn owned_count(
+		&self,
+		account: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<u32> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.owned_count(&at, account).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn kitty_details(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<KittyInfo<AccountId, KittyIndex, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.kitty_details(&at, kitty_id).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn total_supply(&self, at: Option<<Block as BlockT>::Hash>) -> RpcResult<KittyIndex> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.total_supply(&at).map_err(runtime_error_into_rpc_err)
+	}
+}
@@ -1,20 +1,418 @@
-//! Benchmarking setup for pallet-template
+//! Benchmarking setup for pallet-kitties
 
 use super::*;
 
 #[allow(unused)]
-use crate::Pallet as Template;
-use frame_benchmarking::{benchmarks, whitelisted_caller};
+use crate::Pallet as KittiesModule;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::{
+	traits::{Currency, Get, ReservableCurrency},
+	BoundedVec,
+};
 use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, Hash as HashT, One, Saturating, Zero};
+use sp_std::{vec, vec::Vec};
+
+const SEED: u32 = 0;
+
+//创建和繁殖时承诺的秘密
+const DNA_SECRET: [u8; 32] = [7u8; 32];
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+//给账户足够的余额，能够质押多只kitty和支付配种费用
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, SEED);
+	fund::<T>(&who);
+	who
+}
+
+fn fund<T: Config>(who: &T::AccountId) {
+	let balance = T::KittyPrice::get().saturating_mul(1_000u32.into());
+	T::Currency::make_free_balance_be(who, balance);
+}
+
+//直接写入存储创建一只dna已经揭示的kitty，并质押kitty价格
+fn create_kitty<T: Config>(owner: &T::AccountId, dna: [u8; 16]) -> T::KittyIndex {
+	let kitty_id = NextKittyId::<T>::get();
+	let now = frame_system::Pallet::<T>::block_number();
+	let kitty = Kitty { dna, asset: 0, parents: None, generation: 0, birth: now };
+
+	T::Currency::reserve(owner, T::KittyPrice::get()).expect("account is funded");
+
+	Kitties::<T>::insert(kitty_id, kitty);
+	KittyDeposit::<T>::insert(kitty_id, T::KittyPrice::get());
+	DnaRevealedAt::<T>::insert(kitty_id, now);
+	KittyOwner::<T>::insert(kitty_id, owner);
+	OwnedKitties::<T>::insert(owner, kitty_id, ());
+	OwnedKittiesCount::<T>::mutate(owner, |count| *count += 1);
+	NextKittyId::<T>::put(kitty_id + One::one());
+
+	kitty_id
+}
+
+//让账户拥有 n 只kitty，最坏情况下账户名下只差一只就达到上限
+fn fill_kitties<T: Config>(owner: &T::AccountId, n: u32) {
+	for _ in 0..n {
+		create_kitty::<T>(owner, [0u8; 16]);
+	}
+}
+
+//给kitty设置最长的名字和元数据地址，转移时需要一起转移押金
+fn set_max_metadata<T: Config>(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+	let name: BoundedVec<u8, T::MaxNameLength> =
+		vec![b'n'; T::MaxNameLength::get() as usize].try_into().expect("max length");
+	let metadata_uri: BoundedVec<u8, T::MaxMetadataUriLength> =
+		vec![b'u'; T::MaxMetadataUriLength::get() as usize]
+			.try_into()
+			.expect("max length");
+
+	let bytes = T::MaxNameLength::get().saturating_add(T::MaxMetadataUriLength::get());
+	let deposit = T::MetadataDepositPerByte::get().saturating_mul(bytes.into());
+	T::Currency::reserve(owner, deposit).expect("account is funded");
+
+	KittyMetadataOf::<T>::insert(kitty_id, KittyMetadata { name, metadata_uri, deposit });
+}
+
+//在 end 区块结束的拍卖只差一场就达到上限，新的kitty需要追加到列表末尾
+fn fill_auctions_ending_at<T: Config>(end: T::BlockNumber) {
+	let ids = vec![T::KittyIndex::max_value(); T::MaxAuctionsPerBlock::get() as usize - 1];
+	AuctionsEndingAt::<T>::insert(end, BoundedVec::try_from(ids).expect("below max auctions"));
+}
+
+//开始一场持续最长时间的拍卖，kitty排在结束列表的最后，返回结束区块
+fn start_auction<T: Config>(
+	seller: &T::AccountId,
+	kitty_id: T::KittyIndex,
+	best_bid: Option<(T::AccountId, BalanceOf<T>)>,
+) -> T::BlockNumber {
+	let end =
+		frame_system::Pallet::<T>::block_number().saturating_add(T::MaxAuctionDuration::get());
+	fill_auctions_ending_at::<T>(end);
+	AuctionsEndingAt::<T>::mutate(end, |kitties| kitties.try_push(kitty_id).expect("max auctions"));
+
+	let price = T::KittyPrice::get();
+	Auctions::<T>::insert(
+		kitty_id,
+		Auction {
+			seller: seller.clone(),
+			reserve_price: price,
+			min_increment: price,
+			end,
+			best_bid,
+		},
+	);
+
+	end
+}
+
+//写入 n 个授权账户
+fn fill_authorities<T: Config>(n: u32) -> Vec<T::AccountId> {
+	let authorities: Vec<T::AccountId> =
+		(0..n).map(|i| account::<T::AccountId>("authority", i, SEED)).collect();
+	Authorities::<T>::put(
+		BoundedVec::<T::AccountId, T::MaxAuthorities>::try_from(authorities.clone())
+			.expect("within max authorities"),
+	);
+	authorities
+}
 
 benchmarks! {
-	do_something {
-		let s in 0 .. 100;
+	create {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		fill_kitties::<T>(&caller, T::MaxKittyIndex::get().saturating_sub(1));
+		let kitty_id = NextKittyId::<T>::get();
+	}: _(RawOrigin::Signed(caller.clone()), T::Hashing::hash(&DNA_SECRET))
+	verify {
+		assert_eq!(KittyOwner::<T>::get(kitty_id), Some(caller.clone()));
+		assert_eq!(OwnedKittiesCount::<T>::get(&caller), T::MaxKittyIndex::get());
+	}
+
+	//父母都是别人提供配种服务的kitty，需要支付两次配种费用
+	breed {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		fill_kitties::<T>(&caller, T::MaxKittyIndex::get().saturating_sub(1));
+
+		let sire_owner = funded_account::<T>("sire", 0);
+		let dam_owner = funded_account::<T>("dam", 0);
+		let sire = create_kitty::<T>(&sire_owner, [0u8; 16]);
+		let dam = create_kitty::<T>(&dam_owner, [1u8; 16]);
+		SiringOffers::<T>::insert(sire, T::KittyPrice::get());
+		SiringOffers::<T>::insert(dam, T::KittyPrice::get());
+
+		let kitty_id = NextKittyId::<T>::get();
+		let max_siring_fee = T::KittyPrice::get().saturating_add(T::KittyPrice::get());
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		sire,
+		dam,
+		max_siring_fee,
+		T::Hashing::hash(&DNA_SECRET)
+	)
+	verify {
+		assert_eq!(KittyOwner::<T>::get(kitty_id), Some(caller.clone()));
+		assert_eq!(ChildrenCount::<T>::get(sire), 1);
+		assert_eq!(ChildrenCount::<T>::get(dam), 1);
+	}
+
+	//接收者名下只差一只就达到上限，kitty带有最长的元数据，押金需要一起转移
+	transfer {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller, [0u8; 16]);
+		set_max_metadata::<T>(&caller, kitty_id);
+
+		let recipient = funded_account::<T>("recipient", 0);
+		fill_kitties::<T>(&recipient, T::MaxKittyIndex::get().saturating_sub(1));
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, recipient.clone())
+	verify {
+		assert_last_event::<T>(Event::KittyTransferred(caller, recipient, kitty_id).into());
+	}
+
+	//操作员代替拥有者授权，需要额外读取操作员授权
+	approve {
+		let owner = funded_account::<T>("owner", 0);
+		let kitty_id = create_kitty::<T>(&owner, [0u8; 16]);
+		let caller: T::AccountId = whitelisted_caller();
+		OperatorApprovals::<T>::insert(&owner, &caller, true);
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+	}: _(RawOrigin::Signed(caller), kitty_id, Some(delegate.clone()))
+	verify {
+		assert_last_event::<T>(Event::Approval(owner, Some(delegate), kitty_id).into());
+	}
+
+	set_approval_for_all {
+		let caller: T::AccountId = whitelisted_caller();
+		let operator: T::AccountId = account("operator", 0, SEED);
+	}: _(RawOrigin::Signed(caller.clone()), operator.clone(), true)
+	verify {
+		assert_last_event::<T>(Event::ApprovalForAll(caller, operator, true).into());
+	}
+
+	//操作员转移带有最长元数据的kitty，单只授权和操作员授权都要读取
+	transfer_from {
+		let owner = funded_account::<T>("owner", 0);
+		let kitty_id = create_kitty::<T>(&owner, [0u8; 16]);
+		set_max_metadata::<T>(&owner, kitty_id);
+		let caller: T::AccountId = whitelisted_caller();
+		OperatorApprovals::<T>::insert(&owner, &caller, true);
+
+		let recipient = funded_account::<T>("recipient", 0);
+		fill_kitties::<T>(&recipient, T::MaxKittyIndex::get().saturating_sub(1));
+	}: _(RawOrigin::Signed(caller), owner.clone(), recipient.clone(), kitty_id)
+	verify {
+		assert_last_event::<T>(Event::KittyTransferred(owner, recipient, kitty_id).into());
+	}
+
+	//带有最长元数据、挂单和配种服务的kitty，销毁时需要一起移除并释放押金。
+	//dna刚刚揭示，还在冷却期内，kitty押金被没收
+	burn {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller, [0u8; 16]);
+		set_max_metadata::<T>(&caller, kitty_id);
+		KittiesForSale::<T>::insert(kitty_id, T::KittyPrice::get());
+		SiringOffers::<T>::insert(kitty_id, T::KittyPrice::get());
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert_last_event::<T>(Event::KittyBurned(caller.clone(), kitty_id).into());
+		assert_eq!(T::Currency::reserved_balance(&caller), Zero::zero());
+	}
+
+	offer_siring {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller, [0u8; 16]);
+		let fee = T::KittyPrice::get();
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, fee)
+	verify {
+		assert_last_event::<T>(Event::SiringOffered(caller, kitty_id, fee).into());
+	}
+
+	cancel_siring {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller, [0u8; 16]);
+		SiringOffers::<T>::insert(kitty_id, T::KittyPrice::get());
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert_last_event::<T>(Event::SiringCancelled(caller, kitty_id).into());
+	}
+
+	set_price {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller, [0u8; 16]);
+		let price = T::KittyPrice::get();
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, price)
+	verify {
+		assert_last_event::<T>(Event::KittyListed(caller, kitty_id, price).into());
+	}
+
+	cancel_sale {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller, [0u8; 16]);
+		KittiesForSale::<T>::insert(kitty_id, T::KittyPrice::get());
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert_last_event::<T>(Event::SaleCancelled(caller, kitty_id).into());
+	}
+
+	//买家名下只差一只就达到上限，kitty带有最长的元数据，押金需要一起转移
+	buy {
+		let seller = funded_account::<T>("seller", 0);
+		let kitty_id = create_kitty::<T>(&seller, [0u8; 16]);
+		set_max_metadata::<T>(&seller, kitty_id);
+		let price = T::KittyPrice::get();
+		KittiesForSale::<T>::insert(kitty_id, price);
+
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		fill_kitties::<T>(&caller, T::MaxKittyIndex::get().saturating_sub(1));
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, price)
+	verify {
+		assert_last_event::<T>(Event::KittySold(seller, caller, kitty_id, price).into());
+	}
+
+	//结束区块的拍卖列表只差一场就满，挂单需要一起移除
+	create_auction {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller, [0u8; 16]);
+		KittiesForSale::<T>::insert(kitty_id, T::KittyPrice::get());
+
+		let price = T::KittyPrice::get();
+		let end =
+			frame_system::Pallet::<T>::block_number().saturating_add(T::MaxAuctionDuration::get());
+		fill_auctions_ending_at::<T>(end);
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, price, price, end)
+	verify {
+		assert_last_event::<T>(Event::AuctionCreated(caller, kitty_id, price, price, end).into());
+	}
+
+	//超过之前的最高出价，需要退还之前的出价；kitty带有最长的元数据，出价时检查成交后的押金
+	bid {
+		let seller = funded_account::<T>("seller", 0);
+		let kitty_id = create_kitty::<T>(&seller, [0u8; 16]);
+		set_max_metadata::<T>(&seller, kitty_id);
+
+		let price = T::KittyPrice::get();
+		let previous = funded_account::<T>("bidder", 0);
+		T::Currency::reserve(&previous, price).expect("account is funded");
+		start_auction::<T>(&seller, kitty_id, Some((previous, price)));
+
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let amount = price.saturating_add(price);
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, amount)
+	verify {
+		assert_last_event::<T>(Event::BidPlaced(caller, kitty_id, amount).into());
+	}
+
+	//kitty排在结束列表的最后
+	cancel_auction {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller, [0u8; 16]);
+		start_auction::<T>(&caller, kitty_id, None);
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert_last_event::<T>(Event::AuctionCancelled(caller, kitty_id).into());
+	}
+
+	//到期区块的出借列表只差一只就满，挂单和配种服务需要一起移除
+	lend {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller, [0u8; 16]);
+		KittiesForSale::<T>::insert(kitty_id, T::KittyPrice::get());
+		SiringOffers::<T>::insert(kitty_id, T::KittyPrice::get());
+
+		let borrower: T::AccountId = account("borrower", 0, SEED);
+		let until =
+			frame_system::Pallet::<T>::block_number().saturating_add(T::MaxLoanDuration::get());
+		let ids = vec![T::KittyIndex::max_value(); T::MaxLoansPerBlock::get() as usize - 1];
+		LoansEndingAt::<T>::insert(until, BoundedVec::try_from(ids).expect("below max loans"));
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, borrower.clone(), until)
+	verify {
+		assert_last_event::<T>(Event::KittyLent(caller, borrower, kitty_id, until).into());
+	}
+
+	//名字和元数据地址一共 b 个字节，第一次设置需要锁定全部押金
+	set_kitty_metadata {
+		let b in 0 .. T::MaxNameLength::get() + T::MaxMetadataUriLength::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller, [0u8; 16]);
+
+		let name_length = b.min(T::MaxNameLength::get());
+		let name = vec![b'n'; name_length as usize];
+		let metadata_uri = vec![b'u'; (b - name_length) as usize];
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, name.clone(), metadata_uri.clone())
+	verify {
+		assert_last_event::<T>(Event::MetadataSet(caller, kitty_id, name, metadata_uri).into());
+	}
+
+	//授权账户排在列表的最后，图片地址写入还没有元数据的kitty
+	update_kitty {
+		let u in 0 .. T::MaxMetadataUriLength::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		let mut authorities = Vec::new();
+		for i in 1..T::MaxAuthorities::get() {
+			authorities.push(account::<T::AccountId>("authority", i, SEED));
+		}
+		authorities.push(caller.clone());
+		Authorities::<T>::put(
+			BoundedVec::<T::AccountId, T::MaxAuthorities>::try_from(authorities)
+				.expect("within max authorities")
+		);
+
+		let owner = funded_account::<T>("owner", 0);
+		let kitty_id = create_kitty::<T>(&owner, [0u8; 16]);
+		let metadata_uri = vec![b'u'; u as usize];
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, 1, Some(metadata_uri))
+	verify {
+		assert_last_event::<T>(Event::KittyUpdated(caller, kitty_id, 1).into());
+	}
+
+	//新的授权账户需要和列表中已有的账户逐一比较
+	add_authority {
+		fill_authorities::<T>(T::MaxAuthorities::get().saturating_sub(1));
+		let authority: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Root, authority.clone())
+	verify {
+		assert_last_event::<T>(Event::AuthorityAdded(authority).into());
+	}
+
+	//移除满列表中的最后一个授权账户
+	remove_authority {
+		let authority = fill_authorities::<T>(T::MaxAuthorities::get())
+			.pop()
+			.expect("max authorities is not zero");
+	}: _(RawOrigin::Root, authority.clone())
+	verify {
+		assert_last_event::<T>(Event::AuthorityRemoved(authority).into());
+	}
+
+	//繁殖出来的kitty，揭示时需要混合父母的dna
+	reveal_dna {
 		let caller: T::AccountId = whitelisted_caller();
-	}: _(RawOrigin::Signed(caller), s)
+		fund::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller, [0u8; 16]);
+		DnaRevealedAt::<T>::remove(kitty_id);
+		DnaCommitments::<T>::insert(kitty_id, T::Hashing::hash(&DNA_SECRET));
+		DrawnDna::<T>::insert(kitty_id, (DnaSource::Bred([0u8; 16], [1u8; 16]), [2u8; 16]));
+	}: _(RawOrigin::Signed(caller), kitty_id, DNA_SECRET)
 	verify {
-		assert_eq!(Something::<T>::get(), Some(s));
+		assert!(KittiesModule::<T>::is_dna_revealed(kitty_id));
 	}
 
-	impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
+	impl_benchmark_test_suite!(KittiesModule, crate::mock::new_test_ext(), crate::mock::Test);
 }
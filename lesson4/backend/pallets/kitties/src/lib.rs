@@ -2,6 +2,8 @@
 
 //pallet 导出
 pub use pallet::*;
+pub use traits::{KittyInspect, KittyTransfer, OnKittyTransfer};
+pub use weights::WeightInfo;
 
 use sp_core::crypto::KeyTypeId;
 
@@ -16,6 +18,14 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod weights;
+
+//供其它pallet使用的kitty接口
+pub mod traits;
+
+//存储迁移
+pub mod migrations;
+
 //构建加密账户和依赖
 pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"kty!");
 
@@ -50,23 +60,41 @@ pub mod crypto {
 pub mod pallet {
 
 	//引入类型或函数
-	use frame_support::traits::{Randomness, ReservableCurrency};
-	use frame_support::{log, pallet_prelude::*, traits::Currency}; //Currency,固定引入方法
+	use frame_support::traits::{
+		BalanceStatus, ExistenceRequirement, OnRuntimeUpgrade, Randomness, ReservableCurrency,
+	};
+	use frame_support::{log, pallet_prelude::*, traits::Currency, transactional}; //Currency,固定引入方法
 	use frame_system::pallet_prelude::*; //比如一些方便签名和验证的方法
 	use sp_io::hashing::blake2_128;
 
+	use super::{KittyInspect, KittyTransfer, OnKittyTransfer, WeightInfo};
+
+	#[cfg(feature = "std")]
+	use serde::{Deserialize, Serialize};
+
 	use frame_support::inherent::Vec;
-	use frame_system::offchain::SendSignedTransaction;
-	use frame_system::offchain::{AppCrypto, CreateSignedTransaction, Signer};
+	use frame_system::offchain::SendUnsignedTransaction;
+	use frame_system::offchain::{AppCrypto, CreateSignedTransaction, SignedPayload, Signer};
 	use sp_io::offchain_index;
-	use sp_runtime::offchain::storage::StorageValueRef;
-	use sp_runtime::traits::Zero;
+	use sp_runtime::offchain::{
+		storage::StorageValueRef,
+		storage_lock::{BlockAndTime, StorageLock},
+		Duration,
+	};
 
 	//定义新类型，并想为其实现一些必要的trait时，可以直接引用现成的类型，无需重新定义trait
-	use sp_runtime::traits::{AtLeast32Bit, Bounded, CheckedAdd}; //引入trait
+	use sp_runtime::traits::{
+		AtLeast32Bit, Bounded, CheckedAdd, Hash as HashT, IdentifyAccount, Saturating, Zero,
+	}; //引入trait
+
+	//存储版本，结构发生变化时需要升级并提供迁移
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+	//每个dna字节发生突变的概率为 MUTATION_RATE / 256
+	const MUTATION_RATE: u8 = 8;
 
 	//定义一个类型别名,使用Currency这个trait，先得引入，如下也是常见写法，凡是涉及到金钱的，这个类型必不可少
-	type BalanceOf<T> =
+	pub type BalanceOf<T> =
 		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 	//定义类型(类型别名，在业务中易于识别),本来这个类型我们是定义在trait外部的，但是现在改为定义在trait内部，然后用的时候指定
@@ -80,17 +108,109 @@ pub mod pallet {
 		0_u8.into()
 	}
 
+	//定义性别，由dna决定
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum Gender {
+		Male,
+		Female,
+	}
+
 	//定义业务数据
 	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
-	pub struct Kitty {
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct Kitty<KittyIndex, BlockNumber> {
 		pub dna: [u8; 16],
 		pub asset: u32,
+		//父母的id，创建出来的kitty没有父母
+		pub parents: Option<(KittyIndex, KittyIndex)>,
+		//代数，创建出来的kitty为第0代，繁殖出来的kitty为父母中较大代数加1
+		pub generation: u32,
+		//出生的区块
+		pub birth: BlockNumber,
+	}
+
+	impl<KittyIndex, BlockNumber> Kitty<KittyIndex, BlockNumber> {
+		//dna第一个字节的最低位决定性别
+		pub fn gender(&self) -> Gender {
+			if self.dna[0] % 2 == 0 {
+				Gender::Male
+			} else {
+				Gender::Female
+			}
+		}
 	}
 
+	pub type KittyOf<T> =
+		Kitty<<T as Config>::KittyIndex, <T as frame_system::Config>::BlockNumber>;
+
+	//通过runtime api查询kitty详情时返回的数据
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct KittyInfo<AccountId, KittyIndex, BlockNumber> {
+		pub owner: AccountId,
+		pub gender: Gender,
+		pub kitty: Kitty<KittyIndex, BlockNumber>,
+		pub name: Vec<u8>,
+		pub metadata_uri: Vec<u8>,
+	}
+
+	//kitty的名字和元数据地址，押金由设置元数据的拥有者按字节数锁定
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(NameLimit, UriLimit))]
+	#[codec(mel_bound(Balance: MaxEncodedLen))]
+	pub struct KittyMetadata<Balance, NameLimit: Get<u32>, UriLimit: Get<u32>> {
+		pub name: BoundedVec<u8, NameLimit>,
+		pub metadata_uri: BoundedVec<u8, UriLimit>,
+		pub deposit: Balance,
+	}
+
+	pub type MetadataOf<T> = KittyMetadata<
+		BalanceOf<T>,
+		<T as Config>::MaxNameLength,
+		<T as Config>::MaxMetadataUriLength,
+	>;
+
+	//英式拍卖，出价只能越来越高，结束区块时由最高出价者买下
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct Auction<AccountId, Balance, BlockNumber> {
+		pub seller: AccountId,
+		//第一次出价不能低于底价
+		pub reserve_price: Balance,
+		//之后每次出价至少要比当前最高价高出的金额
+		pub min_increment: Balance,
+		//拍卖在这个区块的 on_initialize 中结算
+		pub end: BlockNumber,
+		//当前最高出价者和出价，出价被锁定
+		pub best_bid: Option<(AccountId, Balance)>,
+	}
+
+	pub type AuctionOf<T> = Auction<
+		<T as frame_system::Config>::AccountId,
+		BalanceOf<T>,
+		<T as frame_system::Config>::BlockNumber,
+	>;
+
+	//待揭示dna的来源，繁殖出来的kitty需要保存父母在繁殖时的dna
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum DnaSource {
+		Created,
+		Bred([u8; 16], [u8; 16]),
+	}
+
+	pub type KittyInfoOf<T> = KittyInfo<
+		<T as frame_system::Config>::AccountId,
+		<T as Config>::KittyIndex,
+		<T as frame_system::Config>::BlockNumber,
+	>;
+
 	//定义接口
 	#[pallet::config]
 	pub trait Config: frame_system::Config + CreateSignedTransaction<Call<Self>> {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		//dna的随机源，生产环境应该使用BABE的VRF，比如 pallet_babe::RandomnessFromOneEpochAgo。
+		//出块者可以影响随机数，所以dna还要混合创建者承诺的秘密，见 reveal_dna
 		type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
 
 		//定义关联类型 Currency
@@ -100,19 +220,98 @@ pub mod pallet {
 		//例子：无符号整数类型定义模版
 		type KittyIndex: Parameter + AtLeast32Bit + Default + Copy + Bounded + MaxEncodedLen;
 
-		//定义常量
+		//定义常量，每个账户最多拥有的kitty数量
 		#[pallet::constant]
 		type MaxKittyIndex: Get<u32>;
 
 		#[pallet::constant]
 		type KittyPrice: Get<BalanceOf<Self>>;
 
+		//繁殖后父母需要等待的区块数
+		#[pallet::constant]
+		type BreedCooldown: Get<Self::BlockNumber>;
+
+		//每只kitty最多能繁殖的后代数量
+		#[pallet::constant]
+		type MaxChildren: Get<u32>;
+
+		//创建或繁殖后经过多少个区块才揭示dna，创建时无法预知揭示时的随机数
+		#[pallet::constant]
+		type DnaRevealDelay: Get<Self::BlockNumber>;
+
+		//同一个区块中最多揭示的dna数量，限制 on_initialize 的权重
+		#[pallet::constant]
+		type MaxRevealsPerBlock: Get<u32>;
+
+		//揭示dna之后多少个区块内销毁kitty会没收押金，反复创建和销毁来挑选dna需要付出代价
+		#[pallet::constant]
+		type BurnCooldown: Get<Self::BlockNumber>;
+
+		//拍卖最长持续的区块数
+		#[pallet::constant]
+		type MaxAuctionDuration: Get<Self::BlockNumber>;
+
+		//同一个区块中最多结束的拍卖数量，限制 on_initialize 的权重
+		#[pallet::constant]
+		type MaxAuctionsPerBlock: Get<u32>;
+
+		//出借kitty最长的区块数
+		#[pallet::constant]
+		type MaxLoanDuration: Get<Self::BlockNumber>;
+
+		//同一个区块中最多到期的出借数量，限制 on_initialize 的权重
+		#[pallet::constant]
+		type MaxLoansPerBlock: Get<u32>;
+
+		//kitty名字的最大字节数
+		#[pallet::constant]
+		type MaxNameLength: Get<u32>;
+
+		//kitty元数据地址的最大字节数
+		#[pallet::constant]
+		type MaxMetadataUriLength: Get<u32>;
+
+		//元数据每个字节需要锁定的押金
+		#[pallet::constant]
+		type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+
 		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+		//链下工作机授权账户的最大数量
+		#[pallet::constant]
+		type MaxAuthorities: Get<u32>;
+
+		//链下工作机提交的不签名交易的优先级
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		//kitty易主或销毁时通知其它pallet，比如让 pallet_kitty_swap 删除失效的交换
+		type OnKittyTransfer: OnKittyTransfer<Self::AccountId, Self::KittyIndex>;
+
+		//调用的权重，按 benchmarking.rs 中的最坏情况估算，见 weights.rs
+		type WeightInfo: WeightInfo;
+	}
+
+	//链下工作机用授权账户的密钥签名的数据，通过不签名交易提交，不需要支付手续费
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct KittyAssetPayload<Public, KittyIndex> {
+		pub kitty_id: KittyIndex,
+		pub asset: u32,
+		//链下工作机生成的图片地址，kitty还没有元数据地址时写入
+		pub metadata_uri: Option<Vec<u8>>,
+		pub public: Public,
+	}
+
+	impl<T: Config> SignedPayload<T> for KittyAssetPayload<T::Public, T::KittyIndex> {
+		fn public(&self) -> T::Public {
+			self.public.clone()
+		}
 	}
 
 	//定义Pallet结构体
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)] //生成包含所有存储项的接口
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	//定义存储
@@ -125,31 +324,244 @@ pub mod pallet {
 	//定义存储
 	#[pallet::storage]
 	#[pallet::getter(fn kitties)]
-	pub type Kitties<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, Kitty>;
+	pub type Kitties<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, KittyOf<T>>;
 
 	//定义存储
 	#[pallet::storage]
 	#[pallet::getter(fn kitty_owner)]
 	pub type KittyOwner<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
 
-	//定义存储
+	//定义存储，账户拥有的kitty，转移时直接增删，不需要遍历账户名下所有的kitty
+	#[pallet::storage]
+	pub type OwnedKitties<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::KittyIndex,
+		(),
+		OptionQuery,
+	>;
+
+	//定义存储，账户拥有的kitty数量
+	#[pallet::storage]
+	#[pallet::getter(fn owned_count)]
+	pub type OwnedKittiesCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	//定义存储，kitty可以再次繁殖的区块
+	#[pallet::storage]
+	#[pallet::getter(fn ready_at)]
+	pub type KittyReadyAt<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, T::BlockNumber, ValueQuery>;
+
+	//定义存储，kitty已繁殖的后代数量
+	#[pallet::storage]
+	#[pallet::getter(fn children_count)]
+	pub type ChildrenCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, u32, ValueQuery>;
+
+	//定义存储，kitty的名字和元数据地址
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_metadata)]
+	pub type KittyMetadataOf<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, MetadataOf<T>>;
+
+	//定义存储，正在拍卖的kitty
+	#[pallet::storage]
+	#[pallet::getter(fn auctions)]
+	pub type Auctions<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, AuctionOf<T>>;
+
+	//定义存储，在某个区块结束的拍卖
+	#[pallet::storage]
+	#[pallet::getter(fn auctions_ending_at)]
+	pub type AuctionsEndingAt<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<T::KittyIndex, T::MaxAuctionsPerBlock>,
+		ValueQuery,
+	>;
+
+	//定义存储，出借中的kitty的持有者和归还的区块，出借期间 KittyOwner 仍然是出借者
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_holder)]
+	pub type KittyHolder<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, (T::AccountId, T::BlockNumber)>;
+
+	//定义存储，在某个区块到期归还的kitty
+	#[pallet::storage]
+	#[pallet::getter(fn loans_ending_at)]
+	pub type LoansEndingAt<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<T::KittyIndex, T::MaxLoansPerBlock>,
+		ValueQuery,
+	>;
+
+	//定义存储，等待揭示dna的kitty，按揭示的区块分组，在该区块的 on_initialize 中揭示
+	#[pallet::storage]
+	#[pallet::getter(fn pending_dna)]
+	pub type PendingDna<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Blake2_128Concat,
+		T::KittyIndex,
+		DnaSource,
+	>;
+
+	//定义存储，在某个区块等待揭示的dna数量，不超过 MaxRevealsPerBlock
+	#[pallet::storage]
+	#[pallet::getter(fn pending_dna_count)]
+	pub type PendingDnaCount<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, u32, ValueQuery>;
+
+	//定义存储，拥有者为kitty锁定的押金，不包括元数据押金。
+	//转移和销毁时只释放这部分押金，不会动到拥有者为出价等其他用途锁定的余额
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_deposit)]
+	pub type KittyDeposit<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, BalanceOf<T>, ValueQuery>;
+
+	//定义存储，创建者在创建或繁殖时承诺的秘密的哈希，揭示dna时需要提供这个秘密
+	#[pallet::storage]
+	#[pallet::getter(fn dna_commitment)]
+	pub type DnaCommitments<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, T::Hash>;
+
+	//定义存储，到了揭示区块已经抽取随机数、等待创建者提供秘密的kitty
+	#[pallet::storage]
+	#[pallet::getter(fn drawn_dna)]
+	pub type DrawnDna<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, (DnaSource, [u8; 16])>;
+
+	//定义存储，kitty的dna揭示的区块，没有记录表示还没有揭示
+	#[pallet::storage]
+	#[pallet::getter(fn dna_revealed_at)]
+	pub type DnaRevealedAt<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, T::BlockNumber>;
+
+	//定义存储，已销毁的kitty数量，总量 = NextKittyId - BurnedKitties
 	#[pallet::storage]
-	# [pallet::getter(fn all_kitties)]
-	pub type AllKitties<T: Config> = StorageMap<
+	#[pallet::getter(fn burned_kitties)]
+	pub type BurnedKitties<T: Config> =
+		StorageValue<_, T::KittyIndex, ValueQuery, GetDefaultValue<T>>;
+
+	//定义存储，挂单出售的kitty及其价格
+	#[pallet::storage]
+	#[pallet::getter(fn kitties_for_sale)]
+	pub type KittiesForSale<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, BalanceOf<T>>;
+
+	//定义存储，提供配种服务的kitty及其费用
+	#[pallet::storage]
+	#[pallet::getter(fn siring_offers)]
+	pub type SiringOffers<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, BalanceOf<T>>;
+
+	//定义存储，单只kitty被授权转移给的账户
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_approvals)]
+	pub type KittyApprovals<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
+
+	//定义存储，拥有者授权的操作员，可以转移拥有者名下所有的kitty
+	#[pallet::storage]
+	#[pallet::getter(fn operator_approvals)]
+	pub type OperatorApprovals<T: Config> = StorageDoubleMap<
 		_,
 		Blake2_128Concat,
 		T::AccountId,
-		BoundedVec<T::KittyIndex, T::MaxKittyIndex>,
+		Blake2_128Concat,
+		T::AccountId,
+		bool,
 		ValueQuery,
 	>;
 
+	//定义存储，可以更新kitty资源编号的链下工作机账户，由 crypto::KittiesAuthId 的公钥导出，由root管理
+	#[pallet::storage]
+	#[pallet::getter(fn authorities)]
+	pub type Authorities<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxAuthorities>, ValueQuery>;
+
+	//创世配置，链启动时就存在的kitty，每项为拥有者和dna
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub kitties: Vec<(T::AccountId, [u8; 16])>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { kitties: Default::default() }
+		}
+	}
+
+	//创世kitty的dna已经确定，不需要等待揭示，资源编号直接由dna计算；拥有者和创建的kitty一样质押kitty价格
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (owner, dna) in self.kitties.iter() {
+				let kitty_id =
+					Pallet::<T>::get_next_id().expect("genesis kitty id is not the max value");
+				let kitty = Kitty {
+					dna: *dna,
+					asset: Pallet::<T>::asset_from_dna(dna),
+					parents: None,
+					generation: 0,
+					birth: Zero::zero(),
+				};
+
+				T::Currency::reserve(owner, T::KittyPrice::get())
+					.expect("genesis kitty owner cannot reserve the kitty price");
+
+				Kitties::<T>::insert(kitty_id, kitty);
+				KittyDeposit::<T>::insert(kitty_id, T::KittyPrice::get());
+				DnaRevealedAt::<T>::insert(kitty_id, T::BlockNumber::zero());
+				KittyOwner::<T>::insert(kitty_id, owner);
+				Pallet::<T>::add_owned_kitty(owner, kitty_id)
+					.expect("genesis kitty owner owns too many kitties");
+
+				let next_kitty_id = kitty_id
+					.checked_add(&(T::KittyIndex::from(1_u8)))
+					.expect("genesis kitty id overflow");
+				NextKittyId::<T>::set(next_kitty_id);
+			}
+		}
+	}
+
 	//定义执行成功事件
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		KittyCreated(T::AccountId, T::KittyIndex, Kitty),
-		KittyBred(T::AccountId, T::KittyIndex, Kitty),
+		KittyCreated(T::AccountId, T::KittyIndex, KittyOf<T>),
+		KittyBred(T::AccountId, T::KittyIndex, KittyOf<T>),
 		KittyTransferred(T::AccountId, T::AccountId, T::KittyIndex),
+		KittyListed(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		KittySold(T::AccountId, T::AccountId, T::KittyIndex, BalanceOf<T>),
+		SaleCancelled(T::AccountId, T::KittyIndex),
+		KittyBurned(T::AccountId, T::KittyIndex),
+		SiringOffered(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		SiringCancelled(T::AccountId, T::KittyIndex),
+		SiringFeePaid(T::AccountId, T::AccountId, T::KittyIndex, BalanceOf<T>),
+		Approval(T::AccountId, Option<T::AccountId>, T::KittyIndex),
+		ApprovalForAll(T::AccountId, T::AccountId, bool),
+		AuthorityAdded(T::AccountId),
+		AuthorityRemoved(T::AccountId),
+		KittyUpdated(T::AccountId, T::KittyIndex, u32),
+		DnaRevealed(T::KittyIndex, [u8; 16]),
+		MetadataSet(T::AccountId, T::KittyIndex, Vec<u8>, Vec<u8>),
+		MetadataUriPopulated(T::KittyIndex, Vec<u8>),
+		AuctionCreated(T::AccountId, T::KittyIndex, BalanceOf<T>, BalanceOf<T>, T::BlockNumber),
+		BidPlaced(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		BidRefunded(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		AuctionCancelled(T::AccountId, T::KittyIndex),
+		AuctionSettled(T::AccountId, T::AccountId, T::KittyIndex, BalanceOf<T>),
+		AuctionUnsold(T::AccountId, T::KittyIndex),
+		KittyLent(T::AccountId, T::AccountId, T::KittyIndex, T::BlockNumber),
+		KittyReturned(T::AccountId, T::AccountId, T::KittyIndex),
+		DepositForfeited(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		DnaSeedDrawn(T::KittyIndex),
 	}
 
 	//定义执行失败错误
@@ -161,64 +573,194 @@ pub mod pallet {
 		SameKittyId,
 		NotEnoughBalance,
 		OwnTooManyKitties,
+		SameGender,
+		KittyNotReady,
+		TooManyChildren,
+		NotOfferedForSiring,
+		NotApproved,
+		NotAuthority,
+		AlreadyAuthority,
+		TooManyAuthorities,
+		NotForSale,
+		BuyOwnKitty,
+		DnaNotRevealed,
+		NameTooLong,
+		MetadataUriTooLong,
+		KittyInAuction,
+		AuctionNotFound,
+		AuctionEnded,
+		InvalidAuctionEnd,
+		TooManyAuctions,
+		BidTooLow,
+		BidOwnAuction,
+		AuctionHasBids,
+		KittyLent,
+		LendToSelf,
+		InvalidLoanEnd,
+		TooManyLoans,
+		PriceTooHigh,
+		SiringFeeTooHigh,
+		TooManyReveals,
+		InvalidMinIncrement,
+		DnaSeedNotDrawn,
+		WrongDnaSecret,
 	}
 
 	const ONCHAIN_TX_KEY: &[u8] = b"kitty_pallet::indexing01";
 
+	//链下队列、扫描游标和锁的key
+	const QUEUE_KEY: &[u8] = b"kitty_pallet::queue";
+	const CURSOR_KEY: &[u8] = b"kitty_pallet::cursor";
+	const LOCK_KEY: &[u8] = b"kitty_pallet::lock";
+
+	//锁最多持有的区块数和毫秒数，避免重叠的链下工作机重复提交
+	const LOCK_BLOCK_EXPIRATION: u32 = 3;
+	const LOCK_TIMEOUT_EXPIRATION: u64 = 6_000;
+
+	//提交失败或者没有上链时的重试次数，以及第一次重试前等待的区块数，之后每次翻倍
+	const MAX_ATTEMPTS: u32 = 5;
+	const RETRY_BASE_DELAY: u32 = 2;
+
+	//每次运行最多扫描的kitty id数，以及队列最多保存的kitty数，没扫描完的id下次从游标继续
+	const MAX_SCANNED_PER_RUN: u32 = 64;
+	const MAX_QUEUE_LENGTH: usize = 512;
+
+	//资源编号的种类，编号从1开始，0表示还未计算
+	const ASSET_KINDS: u32 = 1_000;
+
+	//链下工作机生成的图片地址前缀，后面接资源编号
+	const ASSET_URI_PREFIX: &[u8] = b"kitty://asset/";
+
+	//结算一场拍卖的读写次数：拍卖、拥有者、两个账户的kitty列表、余额、挂单、授权、元数据和易主通知等
+	const SETTLE_AUCTION_READS: u64 = 10;
+	const SETTLE_AUCTION_WRITES: u64 = 11;
+
+	//分页查询时每页最多返回的kitty数量
+	const MAX_KITTIES_PAGE: u32 = 100;
+
+	//计算dna时随机数的subject
+	const DNA_SUBJECT: &[u8] = b"kitty_pallet::dna";
+
+	//不签名交易在交易池中的有效区块数
+	const UNSIGNED_TX_LONGEVITY: u64 = 5;
+
 	#[derive(Debug, Encode, Decode, Default)]
 	// struct IndexingData<T: Config>(T::KittyIndex);
 	struct IndexingData<T: Config>(T::KittyIndex);
 
+	//链下队列中等待更新资源编号的kitty
+	#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
+	struct PendingKitty<KittyIndex, BlockNumber> {
+		kitty_id: KittyIndex,
+		//已经提交的次数
+		attempts: u32,
+		//下一次可以提交的区块
+		next_attempt: BlockNumber,
+	}
+
+	type PendingKittyOf<T> =
+		PendingKitty<<T as Config>::KittyIndex, <T as frame_system::Config>::BlockNumber>;
+
 	#[pallet::hooks]
-	// 沉睡8000ms，根据block number 奇偶性确定kitty id
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		//为到期的kitty抽取dna随机数，结算在这个区块结束的拍卖，并收回到期的出借
+		fn on_initialize(block_number: T::BlockNumber) -> Weight {
+			Self::draw_dna_seeds(block_number)
+				.saturating_add(Self::settle_auctions(block_number))
+				.saturating_add(Self::return_loans(block_number))
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::Migration::<T>::on_runtime_upgrade()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			crate::migrations::Migration::<T>::pre_upgrade()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			crate::migrations::Migration::<T>::post_upgrade()
+		}
+
+		// 把新的kitty放入链下队列，然后为到期的kitty提交资源编号
 		fn offchain_worker(block_number: T::BlockNumber) {
 			log::info!("Hello World from offchain workers!: {:?}", block_number);
 
-			// 获取key
-			let key = Self::derived_key(block_number);
+			let mut lock =
+				StorageLock::<BlockAndTime<frame_system::Pallet<T>>>::with_block_and_time_deadline(
+					LOCK_KEY,
+					LOCK_BLOCK_EXPIRATION,
+					Duration::from_millis(LOCK_TIMEOUT_EXPIRATION),
+				);
+
+			//其他链下工作机正在处理队列
+			let _guard = match lock.try_lock() {
+				Ok(guard) => guard,
+				Err(_) => {
+					log::info!("kitty queue is locked by another offchain worker");
+					return
+				},
+			};
 
-			// log::info!("the new store key is {:?}", key);
+			let queue_ref = StorageValueRef::persistent(QUEUE_KEY);
+			let mut queue =
+				queue_ref.get::<Vec<PendingKittyOf<T>>>().ok().flatten().unwrap_or_default();
 
-			// 读取存储内容
-			let storage_content = StorageValueRef::persistent(&key);
+			Self::enqueue_indexed_kitties(block_number, &mut queue);
+			let queue = Self::process_queue(block_number, queue);
 
-			//读取内容并更新
-			if let Ok(Some(data)) = storage_content.get::<IndexingData<T>>() {
-				// if let Some((Some(data)) = storage_ref.get::<IndexingData>() {
-				// Sleep 8000ms to simulate heavy calculation for kitty asset index.
-				log::info!("It needs a lot of time to create a kitty {:?}", block_number);
-				let timeout = sp_io::offchain::timestamp()
-					.add(sp_runtime::offchain::Duration::from_millis(8000));
-				sp_io::offchain::sleep_until(timeout);
+			queue_ref.set(&queue);
 
-				log::info!("new kitty has been created and stored both onchain and offchain");
+			log::info!(
+				"Leave from offchain workers!: {:?}, {} kitties pending",
+				block_number,
+				queue.len()
+			);
+		}
+	}
 
-				let kitty_id = data.0.into();
+	//定义执行逻辑
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		//只接受授权账户签名、且确实会改变链上数据的更新，每只kitty同时只保留一笔交易
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let (payload, signature) = match call {
+				Call::update_kitty_unsigned { payload, signature } => (payload, signature),
+				_ => return InvalidTransaction::Call.into(),
+			};
+
+			if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
+				return InvalidTransaction::BadProof.into()
+			}
 
-				log::info!("get kitty index from offchain {:?}", kitty_id);
+			let who = payload.public.clone().into_account();
+			if !Self::authorities().contains(&who) {
+				return InvalidTransaction::BadSigner.into()
+			}
 
-				if block_number % 2u32.into() != Zero::zero() {
-					_ = Self::send_signed_tx(kitty_id, 100);
-					log::info!("now the block number is odd, let's update kitty's asset as 100, and kitty id is {:?}",kitty_id);
-				} else {
-					_ = Self::send_signed_tx(kitty_id, 200);
-					log::info!("now the block number is even, let's update kitty's asset as 200, and kitty id is {:?}",kitty_id);
-				}
-			} else {
-				log::info!("No off-chain indexing data retrieved.");
+			match Self::kitties(payload.kitty_id) {
+				Some(kitty) if kitty.asset != payload.asset => {},
+				_ => return InvalidTransaction::Stale.into(),
 			}
 
-			log::info!("Leave from offchain workers!: {:?}", block_number);
+			ValidTransaction::with_tag_prefix("KittiesOffchainWorker")
+				.priority(T::UnsignedPriority::get())
+				.and_provides((b"update_kitty", payload.kitty_id))
+				.longevity(UNSIGNED_TX_LONGEVITY)
+				.propagate(true)
+				.build()
 		}
 	}
 
-	//定义执行逻辑
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(10_000)]
-		//创建kitty
-		pub fn create(origin: OriginFor<T>) -> DispatchResult {
+		#[pallet::weight(T::WeightInfo::create())]
+		//创建kitty，dna_commitment 是创建者自己选择的秘密的哈希，之后用 reveal_dna 揭示
+		pub fn create(origin: OriginFor<T>, dna_commitment: T::Hash) -> DispatchResult {
 			//验证签名
 			let who = ensure_signed(origin)?;
 
@@ -231,16 +773,21 @@ pub mod pallet {
 			//获取 kitty_id
 			let kitty_id = Self::get_next_id().map_err(|_| Error::<T>::InvalidKittyId)?;
 
-			//获取dna
-			let dna = Self::random_value(&who);
-			//创造新的 kitty
-			let kitty = Kitty { dna, asset: 0 };
+			//创造新的 kitty，dna在 DnaRevealDelay 个区块之后揭示
+			let now = frame_system::Pallet::<T>::block_number();
+			let reveal_at = now + T::DnaRevealDelay::get();
+			Self::schedule_reveal(reveal_at)?;
+			let kitty =
+				Kitty { dna: [0u8; 16], asset: 0, parents: None, generation: 0, birth: now };
 
 			//质押
 			T::Currency::reserve(&who, kitty_price)?;
 
 			//存储kitty和id
 			Kitties::<T>::insert(kitty_id, &kitty);
+			KittyDeposit::<T>::insert(kitty_id, kitty_price);
+			PendingDna::<T>::insert(reveal_at, kitty_id, DnaSource::Created);
+			DnaCommitments::<T>::insert(kitty_id, dna_commitment);
 
 			//存储kittyId和所有者
 			KittyOwner::<T>::insert(kitty_id, &who);
@@ -254,10 +801,7 @@ pub mod pallet {
 			//设置下一个kitty_id
 			NextKittyId::<T>::set(next_kitty_id);
 
-			AllKitties::<T>::try_mutate(&who, |ref mut kitties| {
-				kitties.try_push(kitty_id).map_err(|_| Error::<T>::OwnTooManyKitties)?;
-				Ok::<(), DispatchError>(())
-			})?;
+			Self::add_owned_kitty(&who, kitty_id)?;
 
 			// 同时把数据存到链下存储中
 			Self::store_kitty_to_indexing(kitty_id);
@@ -267,12 +811,17 @@ pub mod pallet {
 			Ok(())
 		}
 
-		//繁殖kitty
-		#[pallet::weight(10_000)]
+		//繁殖kitty，父母可以是自己的kitty，也可以是别人提供配种服务的kitty。
+		//max_siring_fee 是愿意支付的配种费用总额上限，拥有者在交易上链前提高费用时繁殖失败。
+		//dna_commitment 和创建时相同，是繁殖者选择的秘密的哈希
+		#[pallet::weight(T::WeightInfo::breed())]
+		#[transactional]
 		pub fn breed(
 			origin: OriginFor<T>,
 			kitty_id_1: T::KittyIndex,
 			kitty_id_2: T::KittyIndex,
+			max_siring_fee: BalanceOf<T>,
+			dna_commitment: T::Hash,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
@@ -289,27 +838,73 @@ pub mod pallet {
 			let kitty_1 = Self::get_kitty(kitty_id_1).map_err(|_| Error::<T>::InvalidKittyId)?;
 			let kitty_2 = Self::get_kitty(kitty_id_2).map_err(|_| Error::<T>::InvalidKittyId)?;
 
-			//获取新的kitty的id
-			let kitty_id = Self::get_next_id().map_err(|_| Error::<T>::InvalidKittyId)?;
+			//父母的dna都已经揭示，才能知道性别
+			ensure!(
+				Self::is_dna_revealed(kitty_id_1) && Self::is_dna_revealed(kitty_id_2),
+				Error::<T>::DnaNotRevealed
+			);
 
-			// 得到随机的dna
-			let selector = Self::random_value(&who);
+			//只有异性才能繁殖
+			ensure!(kitty_1.gender() != kitty_2.gender(), Error::<T>::SameGender);
+
+			//不是自己的kitty，需要其拥有者提供了配种服务；出借中的kitty由持有者使用
+			let mut siring_fees = Vec::new();
+			for parent in [kitty_id_1, kitty_id_2] {
+				let owner = Self::kitty_owner(parent).ok_or(Error::<T>::InvalidKittyId)?;
+				let user = Self::kitty_holder(parent).map_or(owner.clone(), |(holder, _)| holder);
+				if user != who {
+					let fee = Self::siring_offers(parent).ok_or(Error::<T>::NotOwner)?;
+					siring_fees.push((owner, parent, fee));
+				}
+			}
 
-			// 定义数据
-			let mut data = [0u8; 16];
+			//配种费用总额不能超过调用者愿意支付的上限
+			let total_fee = siring_fees
+				.iter()
+				.fold(Zero::zero(), |total: BalanceOf<T>, (_, _, fee)| total.saturating_add(*fee));
+			ensure!(total_fee <= max_siring_fee, Error::<T>::SiringFeeTooHigh);
 
-			for i in 0..kitty_1.dna.len() {
-				data[i] = (kitty_1.dna[i] & selector[i]) | (kitty_2.dna[i] & selector[i]);
-			}
+			//父母都需要过了冷却期，且后代数量没有达到上限
+			ensure!(
+				Self::is_ready_to_breed(kitty_id_1) && Self::is_ready_to_breed(kitty_id_2),
+				Error::<T>::KittyNotReady
+			);
+			ensure!(
+				Self::children_count(kitty_id_1) < T::MaxChildren::get() &&
+					Self::children_count(kitty_id_2) < T::MaxChildren::get(),
+				Error::<T>::TooManyChildren
+			);
 
-			// 生成新kitty
-			let new_kitty = Kitty { dna: data, asset: 0 };
+			//获取新的kitty的id
+			let kitty_id = Self::get_next_id().map_err(|_| Error::<T>::InvalidKittyId)?;
+
+			// 生成新kitty，dna在 DnaRevealDelay 个区块之后由父母的dna和当时的随机数混合得到
+			let now = frame_system::Pallet::<T>::block_number();
+			let new_kitty = Kitty {
+				dna: [0u8; 16],
+				asset: 0,
+				parents: Some((kitty_id_1, kitty_id_2)),
+				generation: kitty_1.generation.max(kitty_2.generation).saturating_add(1),
+				birth: now,
+			};
+
+			let reveal_at = now + T::DnaRevealDelay::get();
+			Self::schedule_reveal(reveal_at)?;
+
+			//支付配种费用
+			for (owner, parent, fee) in siring_fees {
+				T::Currency::transfer(&who, &owner, fee, ExistenceRequirement::KeepAlive)?;
+				Self::deposit_event(Event::SiringFeePaid(who.clone(), owner, parent, fee));
+			}
 
 			//质押
 			T::Currency::reserve(&who, kitty_price)?;
 
 			//保存id
 			Kitties::<T>::insert(kitty_id, &new_kitty);
+			KittyDeposit::<T>::insert(kitty_id, kitty_price);
+			PendingDna::<T>::insert(reveal_at, kitty_id, DnaSource::Bred(kitty_1.dna, kitty_2.dna));
+			DnaCommitments::<T>::insert(kitty_id, dna_commitment);
 			//保存所有者
 			KittyOwner::<T>::insert(kitty_id, &who);
 
@@ -321,10 +916,14 @@ pub mod pallet {
 
 			NextKittyId::<T>::set(next_kitty_id);
 
-			AllKitties::<T>::try_mutate(&who, |ref mut kitties| {
-				kitties.try_push(kitty_id).map_err(|_| Error::<T>::OwnTooManyKitties)?;
-				Ok::<(), DispatchError>(())
-			})?;
+			Self::add_owned_kitty(&who, kitty_id)?;
+
+			//父母进入冷却期
+			let ready_at = now + T::BreedCooldown::get();
+			for parent in [kitty_id_1, kitty_id_2] {
+				KittyReadyAt::<T>::insert(parent, ready_at);
+				ChildrenCount::<T>::mutate(parent, |count| *count = count.saturating_add(1));
+			}
 
 			Self::store_kitty_to_indexing(kitty_id);
 
@@ -333,7 +932,9 @@ pub mod pallet {
 			Ok(())
 		}
 
-		#[pallet::weight(10_000)]
+		//转移kitty，押金由新的拥有者重新锁定，原拥有者的押金被释放
+		#[pallet::weight(T::WeightInfo::transfer())]
+		#[transactional]
 		pub fn transfer(
 			origin: OriginFor<T>,
 			kitty_id: T::KittyIndex,
@@ -341,116 +942,1015 @@ pub mod pallet {
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
-			//获取kitty价格
-			let kitty_price = T::KittyPrice::get();
-
-			//判断钱包余额是否足够
-			ensure!(T::Currency::can_reserve(&who, kitty_price), Error::<T>::NotEnoughBalance);
-
 			//获取要转移的kitty，验证kitty_id有效性
 			Self::get_kitty(kitty_id).map_err(|_| Error::<T>::InvalidKittyId)?;
 
 			//确保是拥有者
 			ensure!(Self::kitty_owner(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
 
-			//释放和锁定金钱
-			T::Currency::unreserve(&who, kitty_price);
-			T::Currency::reserve(&new_owner, kitty_price)?;
-
-			//更改owner，键相同，值覆盖
-			KittyOwner::<T>::insert(kitty_id, &new_owner);
-
-			//移除某个account下的kitties信息
-			AllKitties::<T>::try_mutate(&who, |ref mut kitties| {
-				let index = kitties.iter().position(|&r| r == kitty_id).unwrap();
-				kitties.remove(index);
-				Ok::<(), DispatchError>(())
-			})?;
-
-			//更改新的owner
-			AllKitties::<T>::try_mutate(&new_owner, |ref mut kitties| {
-				// print!("### before push kitty to Kitties");
-				kitties.try_push(kitty_id).map_err(|_| Error::<T>::OwnTooManyKitties)?;
-				// print!("### after push kitty to Kitties");
-				Ok::<(), DispatchError>(())
-			})?;
+			//转移所有权
+			Self::do_transfer(&who, &new_owner, kitty_id)?;
 
 			Self::deposit_event(Event::KittyTransferred(who, new_owner, kitty_id));
 
 			Ok(())
 		}
-		#[pallet::weight(0)]
-		//更新链上数据
-		pub fn update_kitty(
+
+		//授权某个账户转移这只kitty，None表示取消授权。拥有者和操作员都可以授权
+		#[pallet::weight(T::WeightInfo::approve())]
+		pub fn approve(
 			origin: OriginFor<T>,
 			kitty_id: T::KittyIndex,
-			asset: u32,
-		) -> DispatchResultWithPostInfo {
-			let _who = ensure_signed(origin)?;
+			delegate: Option<T::AccountId>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
-			let kitty = Self::get_kitty(kitty_id).map_err(|_| Error::<T>::InvalidKittyId)?;
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(owner == who || Self::operator_approvals(&owner, &who), Error::<T>::NotOwner);
 
-			let new_kitty = Kitty { dna: kitty.dna, asset };
+			match delegate {
+				Some(ref delegate) => KittyApprovals::<T>::insert(kitty_id, delegate),
+				None => KittyApprovals::<T>::remove(kitty_id),
+			}
 
-			Kitties::<T>::insert(kitty_id, &new_kitty);
+			Self::deposit_event(Event::Approval(owner, delegate, kitty_id));
 
-			Ok(().into())
+			Ok(())
 		}
-	}
 
-	//定义辅助性的函数,这些函数不需要weights，但是实际上他们会被需要weights的函数在其内部所调用，间接的也说明并不是无成本的
-	impl<T: Config> Pallet<T> {
-		fn random_value(sender: &T::AccountId) -> [u8; 16] {
-			let payload = (
-				T::Randomness::random_seed(),
-				&sender,
-				<frame_system::Pallet<T>>::extrinsic_index(),
-			);
-			payload.using_encoded(blake2_128)
-		}
+		//授权或取消授权操作员转移自己名下所有的kitty
+		#[pallet::weight(T::WeightInfo::set_approval_for_all())]
+		pub fn set_approval_for_all(
+			origin: OriginFor<T>,
+			operator: T::AccountId,
+			approved: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
-		fn get_next_id() -> Result<T::KittyIndex, ()> {
-			let kitty_id = Self::next_kitty_id();
-			match kitty_id {
-				_ if T::KittyIndex::max_value() <= kitty_id => Err(()),
-				val => Ok(val),
+			if approved {
+				OperatorApprovals::<T>::insert(&who, &operator, true);
+			} else {
+				OperatorApprovals::<T>::remove(&who, &operator);
 			}
-		}
 
-		fn get_kitty(kitty_id: T::KittyIndex) -> Result<Kitty, ()> {
-			match Self::kitties(kitty_id) {
-				Some(kitty) => Ok(kitty),
-				None => Err(()),
-			}
-		}
+			Self::deposit_event(Event::ApprovalForAll(who, operator, approved));
 
-		// 辅助函数
-		fn derived_key(block_number: T::BlockNumber) -> Vec<u8> {
-			block_number.using_encoded(|encoded_bn| {
-				ONCHAIN_TX_KEY
-					.clone()
-					.into_iter()
-					.chain(b"/".into_iter())
-					.chain(encoded_bn)
-					.copied()
-					.collect::<Vec<u8>>()
-			})
+			Ok(())
 		}
 
-		// 写入存储
-		fn store_kitty_to_indexing(kitty_id: T::KittyIndex) {
-			let key = Self::derived_key(frame_system::Pallet::<T>::block_number());
+		//代替拥有者转移kitty，调用者需要是拥有者、被授权账户或操作员
+		#[pallet::weight(T::WeightInfo::transfer_from())]
+		#[transactional]
+		pub fn transfer_from(
+			origin: OriginFor<T>,
+			from: T::AccountId,
+			to: T::AccountId,
+			kitty_id: T::KittyIndex,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
-			// log::info!("the store key is {:?}", key);
+			Self::get_kitty(kitty_id).map_err(|_| Error::<T>::InvalidKittyId)?;
 
-			let data: IndexingData<T> = IndexingData(kitty_id);
-			offchain_index::set(&key, &data.encode());
-			log::info!("kitty id has been stored in offchain storage:{:?}", kitty_id);
+			ensure!(Self::kitty_owner(kitty_id) == Some(from.clone()), Error::<T>::NotOwner);
+			ensure!(Self::is_approved(&who, &from, kitty_id), Error::<T>::NotApproved);
+
+			Self::do_transfer(&from, &to, kitty_id)?;
+
+			Self::deposit_event(Event::KittyTransferred(from, to, kitty_id));
+
+			Ok(())
 		}
 
-		fn send_signed_tx(kitty_id: T::KittyIndex, payload: u32) -> Result<(), &'static str> {
-			let signer = Signer::<T, T::AuthorityId>::all_accounts();
-			if !signer.can_sign() {
+		//销毁kitty并释放押金，id不会被复用。
+		//dna揭示之后才能销毁，揭示后 BurnCooldown 个区块内销毁会没收kitty押金，不能靠反复创建和销毁挑选dna
+		#[pallet::weight(T::WeightInfo::burn())]
+		pub fn burn(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			Self::get_kitty(kitty_id).map_err(|_| Error::<T>::InvalidKittyId)?;
+
+			//确保是拥有者
+			ensure!(Self::kitty_owner(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
+			ensure!(!Auctions::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(!KittyHolder::<T>::contains_key(kitty_id), Error::<T>::KittyLent);
+			let revealed_at = Self::dna_revealed_at(kitty_id).ok_or(Error::<T>::DnaNotRevealed)?;
+
+			//移除拥有者名下的kitty
+			Self::remove_owned_kitty(&who, kitty_id)?;
+
+			Kitties::<T>::remove(kitty_id);
+			KittyOwner::<T>::remove(kitty_id);
+			KittiesForSale::<T>::remove(kitty_id);
+			SiringOffers::<T>::remove(kitty_id);
+			KittyApprovals::<T>::remove(kitty_id);
+			KittyReadyAt::<T>::remove(kitty_id);
+			ChildrenCount::<T>::remove(kitty_id);
+			DnaRevealedAt::<T>::remove(kitty_id);
+			let deposit = KittyDeposit::<T>::take(kitty_id);
+			let metadata = KittyMetadataOf::<T>::take(kitty_id);
+
+			BurnedKitties::<T>::mutate(|burned| *burned += T::KittyIndex::from(1_u8));
+
+			//释放押金，包括元数据押金；冷却期内销毁时kitty押金被没收
+			let now = frame_system::Pallet::<T>::block_number();
+			if now < revealed_at.saturating_add(T::BurnCooldown::get()) {
+				let (_, unslashed) = T::Currency::slash_reserved(&who, deposit);
+				Self::deposit_event(Event::DepositForfeited(
+					who.clone(),
+					kitty_id,
+					deposit.saturating_sub(unslashed),
+				));
+			} else {
+				T::Currency::unreserve(&who, deposit);
+			}
+			if let Some(metadata) = metadata {
+				T::Currency::unreserve(&who, metadata.deposit);
+			}
+
+			T::OnKittyTransfer::on_kitty_transfer(&kitty_id, &who, None);
+
+			Self::deposit_event(Event::KittyBurned(who, kitty_id));
+
+			Ok(())
+		}
+
+		//提供配种服务，其他账户支付费用后可以用这只kitty繁殖，重复调用会更新费用
+		#[pallet::weight(T::WeightInfo::offer_siring())]
+		pub fn offer_siring(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			fee: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			Self::get_kitty(kitty_id).map_err(|_| Error::<T>::InvalidKittyId)?;
+
+			ensure!(Self::kitty_owner(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
+			//出借期间只有持有者可以用它繁殖
+			ensure!(!KittyHolder::<T>::contains_key(kitty_id), Error::<T>::KittyLent);
+
+			SiringOffers::<T>::insert(kitty_id, fee);
+
+			Self::deposit_event(Event::SiringOffered(who, kitty_id, fee));
+
+			Ok(())
+		}
+
+		//取消配种服务
+		#[pallet::weight(T::WeightInfo::cancel_siring())]
+		pub fn cancel_siring(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
+			ensure!(SiringOffers::<T>::contains_key(kitty_id), Error::<T>::NotOfferedForSiring);
+
+			SiringOffers::<T>::remove(kitty_id);
+
+			Self::deposit_event(Event::SiringCancelled(who, kitty_id));
+
+			Ok(())
+		}
+
+		//挂单出售kitty，重复调用会更新价格
+		#[pallet::weight(T::WeightInfo::set_price())]
+		pub fn set_price(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			price: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			Self::get_kitty(kitty_id).map_err(|_| Error::<T>::InvalidKittyId)?;
+
+			//确保是拥有者
+			ensure!(Self::kitty_owner(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
+			ensure!(!Auctions::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(!KittyHolder::<T>::contains_key(kitty_id), Error::<T>::KittyLent);
+
+			KittiesForSale::<T>::insert(kitty_id, price);
+
+			Self::deposit_event(Event::KittyListed(who, kitty_id, price));
+
+			Ok(())
+		}
+
+		//取消出售
+		#[pallet::weight(T::WeightInfo::cancel_sale())]
+		pub fn cancel_sale(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
+			ensure!(KittiesForSale::<T>::contains_key(kitty_id), Error::<T>::NotForSale);
+
+			KittiesForSale::<T>::remove(kitty_id);
+
+			Self::deposit_event(Event::SaleCancelled(who, kitty_id));
+
+			Ok(())
+		}
+
+		//购买kitty，付款、押金和所有权转移要么全部成功，要么全部回滚。
+		//max_price 是买家愿意支付的最高价格，卖家在交易上链前抬高价格时购买失败
+		#[pallet::weight(T::WeightInfo::buy())]
+		#[transactional]
+		pub fn buy(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			max_price: BalanceOf<T>,
+		) -> DispatchResult {
+			let buyer = ensure_signed(origin)?;
+
+			let price = Self::kitties_for_sale(kitty_id).ok_or(Error::<T>::NotForSale)?;
+			let seller = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+
+			//不能购买自己的kitty
+			ensure!(buyer != seller, Error::<T>::BuyOwnKitty);
+			ensure!(price <= max_price, Error::<T>::PriceTooHigh);
+
+			//买家需要支付价格并锁定押金
+			ensure!(
+				T::Currency::free_balance(&buyer) >= price.saturating_add(T::KittyPrice::get()),
+				Error::<T>::NotEnoughBalance
+			);
+
+			//付款
+			T::Currency::transfer(&buyer, &seller, price, ExistenceRequirement::KeepAlive)?;
+
+			//转移所有权，同时会移除挂单
+			Self::do_transfer(&seller, &buyer, kitty_id)?;
+
+			Self::deposit_event(Event::KittySold(seller, buyer, kitty_id, price));
+
+			Ok(())
+		}
+
+		//拍卖kitty，设置底价、最小加价幅度和结束区块，拍卖期间kitty不能转移、出售或销毁
+		#[pallet::weight(T::WeightInfo::create_auction())]
+		pub fn create_auction(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			reserve_price: BalanceOf<T>,
+			min_increment: BalanceOf<T>,
+			end: T::BlockNumber,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			//确保是拥有者
+			ensure!(Self::kitty_owner(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
+			ensure!(!Auctions::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(!KittyHolder::<T>::contains_key(kitty_id), Error::<T>::KittyLent);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(
+				end > now && end <= now.saturating_add(T::MaxAuctionDuration::get()),
+				Error::<T>::InvalidAuctionEnd
+			);
+			//最小加价幅度为0时，出价者可以用相同的价格不断抢走最高出价
+			ensure!(!min_increment.is_zero(), Error::<T>::InvalidMinIncrement);
+
+			AuctionsEndingAt::<T>::try_mutate(end, |kitties| {
+				kitties.try_push(kitty_id).map_err(|_| Error::<T>::TooManyAuctions)
+			})?;
+
+			Auctions::<T>::insert(
+				kitty_id,
+				Auction { seller: who.clone(), reserve_price, min_increment, end, best_bid: None },
+			);
+
+			//拍卖替代挂单
+			KittiesForSale::<T>::remove(kitty_id);
+
+			Self::deposit_event(Event::AuctionCreated(
+				who,
+				kitty_id,
+				reserve_price,
+				min_increment,
+				end,
+			));
+
+			Ok(())
+		}
+
+		//出价，出价被锁定，被超过的出价自动退还
+		#[pallet::weight(T::WeightInfo::bid())]
+		#[transactional]
+		pub fn bid(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let mut auction = Self::auctions(kitty_id).ok_or(Error::<T>::AuctionNotFound)?;
+
+			ensure!(
+				frame_system::Pallet::<T>::block_number() < auction.end,
+				Error::<T>::AuctionEnded
+			);
+			ensure!(who != auction.seller, Error::<T>::BidOwnAuction);
+
+			let min_bid = match auction.best_bid {
+				Some((_, best)) => best.saturating_add(auction.min_increment),
+				None => auction.reserve_price,
+			};
+			ensure!(amount >= min_bid, Error::<T>::BidTooLow);
+
+			//成交时还要锁定kitty的押金，出价时一起检查，避免拍卖因为余额不足而结算失败。
+			//拍卖期间元数据不能修改，押金不会再变化
+			let needed = amount.saturating_add(Self::transfer_deposit(kitty_id));
+			ensure!(T::Currency::can_reserve(&who, needed), Error::<T>::NotEnoughBalance);
+
+			//先锁定新的出价，再退还之前的最高出价
+			T::Currency::reserve(&who, amount).map_err(|_| Error::<T>::NotEnoughBalance)?;
+
+			if let Some((bidder, best)) = auction.best_bid.take() {
+				T::Currency::unreserve(&bidder, best);
+				Self::deposit_event(Event::BidRefunded(bidder, kitty_id, best));
+			}
+
+			auction.best_bid = Some((who.clone(), amount));
+			Auctions::<T>::insert(kitty_id, auction);
+
+			Self::deposit_event(Event::BidPlaced(who, kitty_id, amount));
+
+			Ok(())
+		}
+
+		//取消还没有出价的拍卖
+		#[pallet::weight(T::WeightInfo::cancel_auction())]
+		pub fn cancel_auction(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let auction = Self::auctions(kitty_id).ok_or(Error::<T>::AuctionNotFound)?;
+
+			ensure!(auction.seller == who, Error::<T>::NotOwner);
+			ensure!(auction.best_bid.is_none(), Error::<T>::AuctionHasBids);
+
+			Auctions::<T>::remove(kitty_id);
+			AuctionsEndingAt::<T>::mutate(auction.end, |kitties| {
+				kitties.retain(|&id| id != kitty_id);
+			});
+
+			Self::deposit_event(Event::AuctionCancelled(who, kitty_id));
+
+			Ok(())
+		}
+
+		//把kitty借给其它账户直到 until 区块，期间拥有者不变，持有者可以用它繁殖，到期自动归还
+		#[pallet::weight(T::WeightInfo::lend())]
+		pub fn lend(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			borrower: T::AccountId,
+			until: T::BlockNumber,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			//确保是拥有者
+			ensure!(Self::kitty_owner(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
+			ensure!(borrower != who, Error::<T>::LendToSelf);
+			ensure!(!Auctions::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(!KittyHolder::<T>::contains_key(kitty_id), Error::<T>::KittyLent);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(
+				until > now && until <= now.saturating_add(T::MaxLoanDuration::get()),
+				Error::<T>::InvalidLoanEnd
+			);
+
+			LoansEndingAt::<T>::try_mutate(until, |kitties| {
+				kitties.try_push(kitty_id).map_err(|_| Error::<T>::TooManyLoans)
+			})?;
+
+			KittyHolder::<T>::insert(kitty_id, (borrower.clone(), until));
+
+			//出借期间不能出售，也不能为别人提供配种服务
+			KittiesForSale::<T>::remove(kitty_id);
+			SiringOffers::<T>::remove(kitty_id);
+
+			Self::deposit_event(Event::KittyLent(who, borrower, kitty_id, until));
+
+			Ok(())
+		}
+
+		//设置kitty的名字和元数据地址，按字节数锁定押金，多退少补
+		#[pallet::weight(T::WeightInfo::set_kitty_metadata(
+			(name.len() + metadata_uri.len()) as u32
+		))]
+		pub fn set_kitty_metadata(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			name: Vec<u8>,
+			metadata_uri: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			//确保是拥有者，拍卖期间元数据押金由出价者在成交时锁定，不能修改
+			ensure!(Self::kitty_owner(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
+			ensure!(!Auctions::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+
+			let bounded_name: BoundedVec<u8, T::MaxNameLength> =
+				name.clone().try_into().map_err(|_| Error::<T>::NameTooLong)?;
+			let bounded_uri: BoundedVec<u8, T::MaxMetadataUriLength> =
+				metadata_uri.clone().try_into().map_err(|_| Error::<T>::MetadataUriTooLong)?;
+
+			let bytes = (bounded_name.len() + bounded_uri.len()) as u32;
+			let deposit = T::MetadataDepositPerByte::get().saturating_mul(bytes.into());
+			let old_deposit = Self::kitty_metadata(kitty_id)
+				.map(|metadata| metadata.deposit)
+				.unwrap_or_else(Zero::zero);
+
+			//元数据押金随kitty转移，所以之前的押金一定由当前拥有者锁定
+			if deposit > old_deposit {
+				T::Currency::reserve(&who, deposit - old_deposit)
+					.map_err(|_| Error::<T>::NotEnoughBalance)?;
+			} else {
+				T::Currency::unreserve(&who, old_deposit - deposit);
+			}
+
+			KittyMetadataOf::<T>::insert(
+				kitty_id,
+				KittyMetadata { name: bounded_name, metadata_uri: bounded_uri, deposit },
+			);
+
+			Self::deposit_event(Event::MetadataSet(who, kitty_id, name, metadata_uri));
+
+			Ok(())
+		}
+
+		//更新链上数据，只有链下工作机授权账户可以调用
+		#[pallet::weight(T::WeightInfo::update_kitty(
+			metadata_uri.as_ref().map_or(0, |uri| uri.len() as u32)
+		))]
+		pub fn update_kitty(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			asset: u32,
+			metadata_uri: Option<Vec<u8>>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::do_update_kitty(who, kitty_id, asset, metadata_uri)
+		}
+
+		//链下工作机通过不签名交易更新链上数据，签名和授权已经在 validate_unsigned 中检查
+		#[pallet::weight(T::WeightInfo::update_kitty(
+			payload.metadata_uri.as_ref().map_or(0, |uri| uri.len() as u32)
+		))]
+		pub fn update_kitty_unsigned(
+			origin: OriginFor<T>,
+			payload: KittyAssetPayload<T::Public, T::KittyIndex>,
+			_signature: T::Signature,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+
+			let who = payload.public.into_account();
+
+			Self::do_update_kitty(who, payload.kitty_id, payload.asset, payload.metadata_uri)
+		}
+
+		//添加链下工作机授权账户
+		#[pallet::weight(T::WeightInfo::add_authority())]
+		pub fn add_authority(origin: OriginFor<T>, authority: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+
+			Authorities::<T>::try_mutate(|authorities| {
+				ensure!(!authorities.contains(&authority), Error::<T>::AlreadyAuthority);
+				authorities
+					.try_push(authority.clone())
+					.map_err(|_| Error::<T>::TooManyAuthorities)?;
+				Ok::<(), DispatchError>(())
+			})?;
+
+			Self::deposit_event(Event::AuthorityAdded(authority));
+
+			Ok(())
+		}
+
+		//移除链下工作机授权账户
+		#[pallet::weight(T::WeightInfo::remove_authority())]
+		pub fn remove_authority(origin: OriginFor<T>, authority: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+
+			Authorities::<T>::try_mutate(|authorities| {
+				let index = authorities
+					.iter()
+					.position(|a| a == &authority)
+					.ok_or(Error::<T>::NotAuthority)?;
+				authorities.remove(index);
+				Ok::<(), DispatchError>(())
+			})?;
+
+			Self::deposit_event(Event::AuthorityRemoved(authority));
+
+			Ok(())
+		}
+
+		//提供创建或繁殖时承诺的秘密，和揭示区块抽取的随机数混合得到dna。
+		//出块者可以影响随机数但不知道秘密，创建者知道秘密但承诺时不知道随机数，双方都无法挑选dna。
+		//知道秘密的任何账户都可以揭示，kitty转让后创建者仍然可以揭示
+		#[pallet::weight(T::WeightInfo::reveal_dna())]
+		pub fn reveal_dna(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			secret: [u8; 32],
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			let commitment = Self::dna_commitment(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(T::Hashing::hash(&secret) == commitment, Error::<T>::WrongDnaSecret);
+
+			let (source, random) =
+				DrawnDna::<T>::take(kitty_id).ok_or(Error::<T>::DnaSeedNotDrawn)?;
+			DnaCommitments::<T>::remove(kitty_id);
+
+			let selector = (random, secret).using_encoded(blake2_128);
+			Self::do_reveal_dna(
+				kitty_id,
+				source,
+				selector,
+				frame_system::Pallet::<T>::block_number(),
+			);
+
+			Ok(())
+		}
+	}
+
+	//定义辅助性的函数,这些函数不需要weights，但是实际上他们会被需要weights的函数在其内部所调用，间接的也说明并不是无成本的
+	impl<T: Config> Pallet<T> {
+		//为在这个区块到期的kitty抽取随机数，随机数在提交创建或繁殖交易时还无法得知。
+		//有承诺的kitty等创建者用 reveal_dna 提供秘密，升级前创建的kitty没有承诺，直接揭示。
+		//每个区块抽取的数量在创建和繁殖时由 schedule_reveal 限制
+		fn draw_dna_seeds(block_number: T::BlockNumber) -> Weight {
+			let mut drawn: Weight = 0;
+
+			for (kitty_id, source) in PendingDna::<T>::drain_prefix(block_number) {
+				let (seed, _) = T::Randomness::random(&(DNA_SUBJECT, kitty_id).encode());
+				let random = (seed, kitty_id).using_encoded(blake2_128);
+
+				if DnaCommitments::<T>::contains_key(kitty_id) {
+					DrawnDna::<T>::insert(kitty_id, (source, random));
+					Self::deposit_event(Event::DnaSeedDrawn(kitty_id));
+				} else {
+					Self::do_reveal_dna(kitty_id, source, random, block_number);
+				}
+				drawn += 1;
+			}
+			PendingDnaCount::<T>::remove(block_number);
+
+			T::DbWeight::get().reads_writes(1 + 3 * drawn, 1 + 3 * drawn)
+		}
+
+		//用 selector 生成dna，繁殖出来的kitty按 selector 混合父母的dna
+		fn do_reveal_dna(
+			kitty_id: T::KittyIndex,
+			source: DnaSource,
+			selector: [u8; 16],
+			block_number: T::BlockNumber,
+		) {
+			let dna = match source {
+				DnaSource::Created => selector,
+				DnaSource::Bred(dna_1, dna_2) => Self::mix_dna(&dna_1, &dna_2, &selector),
+			};
+
+			Kitties::<T>::mutate(kitty_id, |kitty| {
+				if let Some(kitty) = kitty {
+					kitty.dna = dna;
+				}
+			});
+			DnaRevealedAt::<T>::insert(kitty_id, block_number);
+
+			Self::deposit_event(Event::DnaRevealed(kitty_id, dna));
+		}
+
+		//在揭示区块预留一个位置，和拍卖一样限制每个区块的数量
+		fn schedule_reveal(reveal_at: T::BlockNumber) -> DispatchResult {
+			PendingDnaCount::<T>::try_mutate(reveal_at, |count| {
+				ensure!(*count < T::MaxRevealsPerBlock::get(), Error::<T>::TooManyReveals);
+				*count += 1;
+				Ok(())
+			})
+		}
+
+		//收回在这个区块到期的出借，kitty回到拥有者手中
+		fn return_loans(block_number: T::BlockNumber) -> Weight {
+			let ending = LoansEndingAt::<T>::take(block_number);
+			let count = ending.len() as Weight;
+
+			for kitty_id in ending {
+				if let Some((holder, _)) = KittyHolder::<T>::take(kitty_id) {
+					if let Some(owner) = Self::kitty_owner(kitty_id) {
+						Self::deposit_event(Event::KittyReturned(owner, holder, kitty_id));
+					}
+				}
+			}
+
+			T::DbWeight::get().reads_writes(1 + 2 * count, 1 + count)
+		}
+
+		//结算在这个区块结束的拍卖，有出价的卖给最高出价者，结算失败时退还出价
+		fn settle_auctions(block_number: T::BlockNumber) -> Weight {
+			let ending = AuctionsEndingAt::<T>::take(block_number);
+			let count = ending.len() as Weight;
+
+			for kitty_id in ending {
+				let auction = match Auctions::<T>::take(kitty_id) {
+					Some(auction) => auction,
+					None => continue,
+				};
+
+				match auction.best_bid {
+					Some((bidder, amount)) =>
+						match Self::settle_auction(kitty_id, &auction.seller, &bidder, amount) {
+							Ok(()) => Self::deposit_event(Event::AuctionSettled(
+								auction.seller,
+								bidder,
+								kitty_id,
+								amount,
+							)),
+							Err(e) => {
+								log::warn!(
+									"failed to settle auction of kitty {:?}: {:?}",
+									kitty_id,
+									e
+								);
+								T::Currency::unreserve(&bidder, amount);
+								Self::deposit_event(Event::BidRefunded(bidder, kitty_id, amount));
+								Self::deposit_event(Event::AuctionUnsold(auction.seller, kitty_id));
+							},
+						},
+					None => Self::deposit_event(Event::AuctionUnsold(auction.seller, kitty_id)),
+				}
+			}
+
+			T::DbWeight::get()
+				.reads_writes(1 + SETTLE_AUCTION_READS * count, 1 + SETTLE_AUCTION_WRITES * count)
+		}
+
+		//把锁定的出价付给卖家并转移kitty，任何一步失败都会回滚
+		#[transactional]
+		fn settle_auction(
+			kitty_id: T::KittyIndex,
+			seller: &T::AccountId,
+			bidder: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			T::Currency::repatriate_reserved(bidder, seller, amount, BalanceStatus::Free)?;
+			Self::do_transfer(seller, bidder, kitty_id)
+		}
+
+		//账户是否可以转移拥有者的这只kitty
+		pub fn is_approved(
+			who: &T::AccountId,
+			owner: &T::AccountId,
+			kitty_id: T::KittyIndex,
+		) -> bool {
+			who == owner ||
+				Self::kitty_approvals(kitty_id).as_ref() == Some(who) ||
+				Self::operator_approvals(owner, who)
+		}
+
+		//kitty的dna是否已经揭示，揭示之前dna全为0
+		pub fn is_dna_revealed(kitty_id: T::KittyIndex) -> bool {
+			DnaRevealedAt::<T>::contains_key(kitty_id)
+		}
+
+		//kitty是否已经过了繁殖冷却期
+		pub fn is_ready_to_breed(kitty_id: T::KittyIndex) -> bool {
+			Self::ready_at(kitty_id) <= frame_system::Pallet::<T>::block_number()
+		}
+
+		//分页查询账户名下kitty的id，供runtime api使用。
+		//按存储顺序返回 start_after 之后的最多 limit 个，下一页传入这一页的最后一个id
+		pub fn kitties_of(
+			account: &T::AccountId,
+			start_after: Option<T::KittyIndex>,
+			limit: u32,
+		) -> Vec<T::KittyIndex> {
+			let kitties = match start_after {
+				Some(kitty_id) => OwnedKitties::<T>::iter_key_prefix_from(
+					account,
+					OwnedKitties::<T>::hashed_key_for(account, kitty_id),
+				),
+				None => OwnedKitties::<T>::iter_key_prefix(account),
+			};
+
+			kitties.take(limit.min(MAX_KITTIES_PAGE) as usize).collect()
+		}
+
+		//账户是否拥有这只kitty
+		pub fn owns(account: &T::AccountId, kitty_id: T::KittyIndex) -> bool {
+			OwnedKitties::<T>::contains_key(account, kitty_id)
+		}
+
+		//把kitty加入账户名下，每个账户最多拥有 MaxKittyIndex 只
+		fn add_owned_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+			OwnedKittiesCount::<T>::try_mutate(owner, |count| {
+				ensure!(*count < T::MaxKittyIndex::get(), Error::<T>::OwnTooManyKitties);
+				*count += 1;
+				Ok::<(), DispatchError>(())
+			})?;
+
+			OwnedKitties::<T>::insert(owner, kitty_id, ());
+
+			Ok(())
+		}
+
+		//把kitty从账户名下移除，账户不拥有这只kitty时返回错误而不是panic
+		fn remove_owned_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+			OwnedKitties::<T>::take(owner, kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			OwnedKittiesCount::<T>::mutate(owner, |count| *count = count.saturating_sub(1));
+
+			Ok(())
+		}
+
+		//kitty的详情，供runtime api使用
+		pub fn kitty_details(kitty_id: T::KittyIndex) -> Option<KittyInfoOf<T>> {
+			let kitty = Self::kitties(kitty_id)?;
+			let owner = Self::kitty_owner(kitty_id)?;
+			let metadata = Self::kitty_metadata(kitty_id);
+			Some(KittyInfo {
+				owner,
+				gender: kitty.gender(),
+				kitty,
+				name: metadata.as_ref().map(|m| m.name.to_vec()).unwrap_or_default(),
+				metadata_uri: metadata.map(|m| m.metadata_uri.into_inner()).unwrap_or_default(),
+			})
+		}
+
+		//当前存在的kitty总量
+		pub fn total_supply() -> T::KittyIndex {
+			Self::next_kitty_id() - Self::burned_kitties()
+		}
+
+		fn get_next_id() -> Result<T::KittyIndex, ()> {
+			let kitty_id = Self::next_kitty_id();
+			match kitty_id {
+				_ if T::KittyIndex::max_value() <= kitty_id => Err(()),
+				val => Ok(val),
+			}
+		}
+
+		//交叉遗传：选择子的某一位为1时取kitty_1对应的基因，否则取kitty_2的，
+		//随后按选择子的哈希对每个字节做概率为 MUTATION_RATE / 256 的单位突变，相同输入得到相同输出
+		pub fn mix_dna(dna_1: &[u8; 16], dna_2: &[u8; 16], selector: &[u8; 16]) -> [u8; 16] {
+			let mutation = blake2_128(selector);
+			let mut dna = [0u8; 16];
+
+			for i in 0..dna.len() {
+				dna[i] = (dna_1[i] & selector[i]) | (dna_2[i] & !selector[i]);
+
+				if mutation[i] < MUTATION_RATE {
+					dna[i] ^= 1 << (mutation[i] % 8);
+				}
+			}
+
+			dna
+		}
+
+		fn get_kitty(kitty_id: T::KittyIndex) -> Result<KittyOf<T>, ()> {
+			match Self::kitties(kitty_id) {
+				Some(kitty) => Ok(kitty),
+				None => Err(()),
+			}
+		}
+
+		//转移kitty：释放旧主人的押金、锁定新主人的押金，并更新所有权和挂单信息
+		#[transactional]
+		fn do_transfer(
+			from: &T::AccountId,
+			to: &T::AccountId,
+			kitty_id: T::KittyIndex,
+		) -> DispatchResult {
+			//拍卖中的kitty只能通过结算转移
+			ensure!(!Auctions::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			//出借中的kitty要等归还之后才能转移
+			ensure!(!KittyHolder::<T>::contains_key(kitty_id), Error::<T>::KittyLent);
+
+			//新的拥有者按当前价格锁定kitty押金，并重新锁定元数据押金，余额不足时不改变任何状态
+			let metadata_deposit = Self::metadata_deposit(kitty_id);
+			let deposit = T::KittyPrice::get().saturating_add(metadata_deposit);
+			ensure!(T::Currency::can_reserve(to, deposit), Error::<T>::NotEnoughBalance);
+
+			//先锁定新的拥有者的押金，再释放原拥有者实际为这只kitty锁定的押金
+			T::Currency::reserve(to, deposit)?;
+			T::Currency::unreserve(
+				from,
+				Self::kitty_deposit(kitty_id).saturating_add(metadata_deposit),
+			);
+			KittyDeposit::<T>::insert(kitty_id, T::KittyPrice::get());
+
+			//更改owner，键相同，值覆盖
+			KittyOwner::<T>::insert(kitty_id, to);
+
+			//移除某个account下的kitties信息
+			Self::remove_owned_kitty(from, kitty_id)?;
+
+			//更改新的owner
+			Self::add_owned_kitty(to, kitty_id)?;
+
+			//易主后挂单、配种服务和授权失效
+			KittiesForSale::<T>::remove(kitty_id);
+			SiringOffers::<T>::remove(kitty_id);
+			KittyApprovals::<T>::remove(kitty_id);
+
+			T::OnKittyTransfer::on_kitty_transfer(&kitty_id, from, Some(to));
+
+			Ok(())
+		}
+
+		//kitty元数据锁定的押金，没有元数据时为0
+		fn metadata_deposit(kitty_id: T::KittyIndex) -> BalanceOf<T> {
+			Self::kitty_metadata(kitty_id).map_or_else(Zero::zero, |metadata| metadata.deposit)
+		}
+
+		//转移kitty时新的拥有者需要锁定的押金：kitty价格和元数据押金
+		fn transfer_deposit(kitty_id: T::KittyIndex) -> BalanceOf<T> {
+			T::KittyPrice::get().saturating_add(Self::metadata_deposit(kitty_id))
+		}
+
+		//链下工作机为资源编号生成的图片地址
+		pub fn asset_uri(asset: u32) -> Vec<u8> {
+			let mut digits = Vec::new();
+			let mut rest = asset;
+			loop {
+				digits.push(b'0' + (rest % 10) as u8);
+				rest /= 10;
+				if rest == 0 {
+					break
+				}
+			}
+			digits.reverse();
+
+			[ASSET_URI_PREFIX, &digits].concat()
+		}
+
+		//根据dna确定性地计算kitty的资源编号
+		pub fn asset_from_dna(dna: &[u8; 16]) -> u32 {
+			let hash = blake2_128(dna);
+			u32::from_le_bytes([hash[0], hash[1], hash[2], hash[3]]) % ASSET_KINDS + 1
+		}
+
+		// 辅助函数，每只kitty使用各自的key，同一个区块中创建的kitty不会互相覆盖
+		fn derived_key(kitty_id: T::KittyIndex) -> Vec<u8> {
+			kitty_id.using_encoded(|encoded_id| {
+				ONCHAIN_TX_KEY
+					.clone()
+					.into_iter()
+					.chain(b"/".into_iter())
+					.chain(encoded_id)
+					.copied()
+					.collect::<Vec<u8>>()
+			})
+		}
+
+		// 写入存储
+		fn store_kitty_to_indexing(kitty_id: T::KittyIndex) {
+			let key = Self::derived_key(kitty_id);
+
+			// log::info!("the store key is {:?}", key);
+
+			let data: IndexingData<T> = IndexingData(kitty_id);
+			offchain_index::set(&key, &data.encode());
+			log::info!("kitty id has been stored in offchain storage:{:?}", kitty_id);
+		}
+
+		//从上次扫描到的id开始，把链下索引中的新kitty放入队列，扫描数量和队列长度都有上限
+		fn enqueue_indexed_kitties(
+			block_number: T::BlockNumber,
+			queue: &mut Vec<PendingKittyOf<T>>,
+		) {
+			let cursor_ref = StorageValueRef::persistent(CURSOR_KEY);
+			let mut cursor = cursor_ref.get::<T::KittyIndex>().ok().flatten().unwrap_or_default();
+			let next_kitty_id = Self::next_kitty_id();
+			let mut scanned = 0;
+
+			while cursor < next_kitty_id &&
+				scanned < MAX_SCANNED_PER_RUN &&
+				queue.len() < MAX_QUEUE_LENGTH
+			{
+				let key = Self::derived_key(cursor);
+				let mut indexing_ref = StorageValueRef::persistent(&key);
+
+				if let Ok(Some(data)) = indexing_ref.get::<IndexingData<T>>() {
+					log::info!("get kitty index from offchain {:?}", data.0);
+					queue.push(PendingKitty {
+						kitty_id: data.0,
+						attempts: 0,
+						next_attempt: block_number,
+					});
+					indexing_ref.clear();
+				}
+
+				cursor += T::KittyIndex::from(1_u8);
+				scanned += 1;
+			}
+
+			cursor_ref.set(&cursor);
+		}
+
+		//处理到期的kitty，返回仍需等待的kitty
+		fn process_queue(
+			block_number: T::BlockNumber,
+			queue: Vec<PendingKittyOf<T>>,
+		) -> Vec<PendingKittyOf<T>> {
+			let mut remaining = Vec::new();
+
+			for mut pending in queue {
+				//kitty已经被销毁
+				let kitty = match Self::kitties(pending.kitty_id) {
+					Some(kitty) => kitty,
+					None => continue,
+				};
+
+				//dna还没有揭示，资源编号要等揭示之后才能计算
+				if !Self::is_dna_revealed(pending.kitty_id) {
+					remaining.push(pending);
+					continue
+				}
+
+				//资源编号已经上链
+				let asset = Self::asset_from_dna(&kitty.dna);
+				if kitty.asset == asset {
+					log::info!("kitty {:?} asset has been updated to {}", pending.kitty_id, asset);
+					continue
+				}
+
+				if pending.next_attempt > block_number {
+					remaining.push(pending);
+					continue
+				}
+
+				if pending.attempts >= MAX_ATTEMPTS {
+					log::error!(
+						"give up updating kitty {:?} after {} attempts",
+						pending.kitty_id,
+						pending.attempts
+					);
+					continue
+				}
+
+				if let Err(e) = Self::send_unsigned_tx(pending.kitty_id, asset) {
+					log::warn!("failed to update kitty {:?}: {}", pending.kitty_id, e);
+				}
+
+				//无论提交是否成功，都等到退避期后再检查是否上链
+				pending.attempts += 1;
+				pending.next_attempt = block_number + Self::retry_delay(pending.attempts);
+				remaining.push(pending);
+			}
+
+			remaining
+		}
+
+		//第n次提交后等待 RETRY_BASE_DELAY * 2^(n-1) 个区块
+		fn retry_delay(attempts: u32) -> T::BlockNumber {
+			(RETRY_BASE_DELAY << attempts.saturating_sub(1).min(16)).into()
+		}
+
+		//更新链上的资源编号
+		fn do_update_kitty(
+			who: T::AccountId,
+			kitty_id: T::KittyIndex,
+			asset: u32,
+			metadata_uri: Option<Vec<u8>>,
+		) -> DispatchResultWithPostInfo {
+			ensure!(Self::authorities().contains(&who), Error::<T>::NotAuthority);
+
+			let kitty = Self::get_kitty(kitty_id).map_err(|_| Error::<T>::InvalidKittyId)?;
+
+			let new_kitty = Kitty { asset, ..kitty };
+
+			Kitties::<T>::insert(kitty_id, &new_kitty);
+
+			//图片地址只写入还没有元数据地址的kitty，不覆盖拥有者设置的地址，也不收取押金
+			if let Some(metadata_uri) = metadata_uri {
+				let bounded_uri: BoundedVec<u8, T::MaxMetadataUriLength> =
+					metadata_uri.clone().try_into().map_err(|_| Error::<T>::MetadataUriTooLong)?;
+
+				let mut metadata =
+					Self::kitty_metadata(kitty_id).unwrap_or_else(|| KittyMetadata {
+						name: Default::default(),
+						metadata_uri: Default::default(),
+						deposit: Zero::zero(),
+					});
+
+				if metadata.metadata_uri.is_empty() {
+					metadata.metadata_uri = bounded_uri;
+					KittyMetadataOf::<T>::insert(kitty_id, metadata);
+					Self::deposit_event(Event::MetadataUriPopulated(kitty_id, metadata_uri));
+				}
+			}
+
+			Self::deposit_event(Event::KittyUpdated(who, kitty_id, asset));
+
+			Ok(().into())
+		}
+
+		//用本地所有的 KittiesAuthId 密钥对数据签名，并以不签名交易提交，节点不需要支付手续费
+		fn send_unsigned_tx(kitty_id: T::KittyIndex, asset: u32) -> Result<(), &'static str> {
+			let signer = Signer::<T, T::AuthorityId>::all_accounts();
+			if !signer.can_sign() {
 				return Err(
 					"No local accounts available. Consider adding one via `author_insertKey` RPC.",
 				);
@@ -458,18 +1958,58 @@ pub mod pallet {
 
 			log::info!("updating kitty asset, {:?}", kitty_id);
 			// update_kitty info
-			let results = signer.send_signed_transaction(|_account| Call::update_kitty {
-				kitty_id,
-				asset: payload,
-			});
+			let results = signer.send_unsigned_transaction(
+				|account| KittyAssetPayload {
+					kitty_id,
+					asset,
+					metadata_uri: Some(Self::asset_uri(asset)),
+					public: account.public.clone(),
+				},
+				|payload, signature| Call::update_kitty_unsigned { payload, signature },
+			);
 
 			for (acc, res) in &results {
 				match res {
-					Ok(()) => log::info!("[{:?}] Submitted data:{:?}", acc.id, (kitty_id, payload)),
-					Err(e) => log::error!("[{:?}] Failed to submit transaction: {:?}", acc.id, e),
+					Ok(()) => log::info!("[{:?}] Submitted data:{:?}", acc.id, (kitty_id, asset)),
+					Err(()) => log::error!("[{:?}] Failed to submit transaction", acc.id),
 				}
 			}
 
+			if results.iter().any(|(_, res)| res.is_ok()) {
+				Ok(())
+			} else {
+				Err("Failed to submit transaction")
+			}
+		}
+	}
+
+	impl<T: Config> KittyInspect<T::AccountId> for Pallet<T> {
+		type KittyId = T::KittyIndex;
+
+		fn owner(kitty_id: &T::KittyIndex) -> Option<T::AccountId> {
+			Self::kitty_owner(kitty_id)
+		}
+
+		fn dna(kitty_id: &T::KittyIndex) -> Option<[u8; 16]> {
+			let kitty = Self::kitties(kitty_id)?;
+			Self::is_dna_revealed(*kitty_id).then(|| kitty.dna)
+		}
+
+		fn can_transfer(kitty_id: &T::KittyIndex) -> bool {
+			KittyOwner::<T>::contains_key(kitty_id) &&
+				!Auctions::<T>::contains_key(kitty_id) &&
+				!KittyHolder::<T>::contains_key(kitty_id)
+		}
+	}
+
+	impl<T: Config> KittyTransfer<T::AccountId> for Pallet<T> {
+		fn transfer(kitty_id: &T::KittyIndex, destination: &T::AccountId) -> DispatchResult {
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+
+			Self::do_transfer(&owner, destination, *kitty_id)?;
+
+			Self::deposit_event(Event::KittyTransferred(owner, destination.clone(), *kitty_id));
+
 			Ok(())
 		}
 	}
@@ -0,0 +1,268 @@
+//kitties 存储迁移
+//
+//版本0：没有声明存储版本的旧链，kitty可能是 lesson2 的 `Kitty([u8; 16])`，也可能是 lesson4 的 `Kitty { dna, asset }`
+//版本1：kitty 增加父母、代数和出生区块
+//版本2：账户拥有的kitty改为 OwnedKitties 双键映射
+//版本3：记录dna揭示的区块、每个区块等待揭示的数量和每只kitty锁定的押金
+
+use crate::*;
+use frame_support::{
+	log,
+	pallet_prelude::*,
+	storage::migration::storage_key_iter,
+	traits::{GetStorageVersion, OnRuntimeUpgrade, PalletInfoAccess, StorageVersion},
+	weights::Weight,
+};
+use sp_runtime::traits::Zero;
+use core::marker::PhantomData;
+use sp_std::collections::btree_set::BTreeSet;
+
+#[cfg(feature = "try-runtime")]
+use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
+//按链上存储版本依次执行迁移，已经执行过的迁移会被跳过
+pub struct Migration<T>(PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+	fn on_runtime_upgrade() -> Weight {
+		v1::migrate::<T>()
+			.saturating_add(v2::migrate::<T>())
+			.saturating_add(v3::migrate::<T>())
+	}
+
+	//记录升级前的kitty数量，并确认旧的kitty都能按当前版本的结构解码
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		let on_chain_version = Pallet::<T>::on_chain_storage_version();
+
+		let count = Kitties::<T>::iter_keys().count() as u32;
+		for kitty_id in Kitties::<T>::iter_keys() {
+			let raw =
+				frame_support::storage::unhashed::get_raw(&Kitties::<T>::hashed_key_for(kitty_id))
+					.ok_or("kitty disappeared while iterating")?;
+
+			if on_chain_version < 1 {
+				v1::V0Kitty::decode(&mut &raw[..]).map_err(|_| "undecodable v0 kitty")?;
+			} else {
+				KittyOf::<T>::decode(&mut &raw[..]).map_err(|_| "undecodable kitty")?;
+			}
+		}
+
+		Self::set_temp_storage(count, "kitty_count");
+		Self::set_temp_storage(Pallet::<T>::next_kitty_id(), "next_kitty_id");
+
+		log::info!("pre-upgrade: {} kitties at storage version {:?}", count, on_chain_version);
+
+		Ok(())
+	}
+
+	//升级后版本为最新，kitty数量和id不变，每只kitty都有拥有者且记录在拥有者名下
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		ensure!(
+			Pallet::<T>::on_chain_storage_version() == Pallet::<T>::current_storage_version(),
+			"storage version was not updated"
+		);
+
+		let count: u32 = Self::get_temp_storage("kitty_count").ok_or("missing kitty count")?;
+		let next_kitty_id: T::KittyIndex =
+			Self::get_temp_storage("next_kitty_id").ok_or("missing next kitty id")?;
+
+		ensure!(Pallet::<T>::next_kitty_id() == next_kitty_id, "next kitty id changed");
+
+		let mut migrated = 0u32;
+		for (kitty_id, _kitty) in Kitties::<T>::iter() {
+			let owner = Pallet::<T>::kitty_owner(kitty_id).ok_or("kitty without owner")?;
+			ensure!(Pallet::<T>::owns(&owner, kitty_id), "kitty missing from owner's list");
+			migrated += 1;
+		}
+		ensure!(migrated == count, "kitties were lost during migration");
+
+		let owned: u32 = OwnedKittiesCount::<T>::iter_values().sum();
+		ensure!(owned == count, "owned kitty counts do not match");
+
+		let revealed = DnaRevealedAt::<T>::iter_keys().count() as u32;
+		let pending = PendingDna::<T>::iter_keys().count() as u32;
+		ensure!(revealed + pending == count, "kitties without reveal status");
+
+		let deposits = KittyDeposit::<T>::iter_keys().count() as u32;
+		ensure!(deposits == count, "kitties without recorded deposit");
+
+		log::info!(
+			"post-upgrade: {} kitties at storage version {:?}",
+			count,
+			Pallet::<T>::current_storage_version()
+		);
+
+		Ok(())
+	}
+}
+
+//v0 -> v1：kitty 增加父母、代数和出生区块
+pub mod v1 {
+	use super::*;
+
+	//lesson2 的kitty结构：只有dna
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	pub struct LegacyKitty(pub [u8; 16]);
+
+	//lesson4 v0 版本的kitty结构：只有dna和asset
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	pub struct OldKitty {
+		pub dna: [u8; 16],
+		pub asset: u32,
+	}
+
+	//两种v0结构都没有版本号，只能按编码长度区分：lesson2 是16个字节，lesson4 是20个字节
+	#[derive(Clone, PartialEq, Eq, Debug)]
+	pub enum V0Kitty {
+		Legacy(LegacyKitty),
+		Old(OldKitty),
+	}
+
+	impl Decode for V0Kitty {
+		fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+			let dna = <[u8; 16]>::decode(input)?;
+
+			match input.remaining_len()? {
+				Some(0) => Ok(V0Kitty::Legacy(LegacyKitty(dna))),
+				_ => Ok(V0Kitty::Old(OldKitty { dna, asset: u32::decode(input)? })),
+			}
+		}
+	}
+
+	impl From<V0Kitty> for OldKitty {
+		//lesson2 的kitty还没有资源编号，和新建的kitty一样为0，由链下工作机补上
+		fn from(kitty: V0Kitty) -> Self {
+			match kitty {
+				V0Kitty::Legacy(LegacyKitty(dna)) => OldKitty { dna, asset: 0 },
+				V0Kitty::Old(old) => old,
+			}
+		}
+	}
+
+	pub fn migrate<T: Config>() -> Weight {
+		let on_chain_version = Pallet::<T>::on_chain_storage_version();
+
+		if on_chain_version >= 1 {
+			log::info!("kitties storage is already at {:?}, skip migration", on_chain_version);
+			return T::DbWeight::get().reads(1)
+		}
+
+		//旧kitty的父母和出生区块无从得知，统一视为第0代、出生于0号区块。
+		//lesson2 和 lesson4 创建和繁殖kitty时都锁定了kitty价格
+		let mut translated = 0u64;
+		Kitties::<T>::translate::<V0Kitty, _>(|kitty_id, kitty| {
+			translated += 1;
+			KittyDeposit::<T>::insert(kitty_id, T::KittyPrice::get());
+
+			let old = OldKitty::from(kitty);
+			Some(Kitty {
+				dna: old.dna,
+				asset: old.asset,
+				parents: None,
+				generation: 0,
+				birth: Zero::zero(),
+			})
+		});
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+
+		log::info!("migrated {} kitties to storage version 1", translated);
+
+		T::DbWeight::get().reads_writes(translated + 1, translated * 2 + 1)
+	}
+}
+
+//v1 -> v2：账户拥有的kitty从 AllKitties 中的 BoundedVec 改为 OwnedKitties 双键映射，并记录数量
+pub mod v2 {
+	use super::*;
+
+	//v1 版本存储账户名下kitty列表的存储项名
+	pub const OLD_ALL_KITTIES: &[u8] = b"AllKitties";
+
+	pub fn migrate<T: Config>() -> Weight {
+		let on_chain_version = Pallet::<T>::on_chain_storage_version();
+
+		if on_chain_version >= 2 {
+			log::info!("kitties storage is already at {:?}, skip migration", on_chain_version);
+			return T::DbWeight::get().reads(1)
+		}
+
+		//BoundedVec 和 Vec 的编码相同，边读边删除旧的存储项
+		let pallet_name = <Pallet<T> as PalletInfoAccess>::name();
+		let mut accounts = 0u64;
+		let mut moved = 0u64;
+		for (owner, kitties) in
+			storage_key_iter::<T::AccountId, Vec<T::KittyIndex>, Blake2_128Concat>(
+				pallet_name.as_bytes(),
+				OLD_ALL_KITTIES,
+			)
+			.drain()
+		{
+			for kitty_id in kitties.iter() {
+				OwnedKitties::<T>::insert(&owner, kitty_id, ());
+			}
+			OwnedKittiesCount::<T>::insert(&owner, kitties.len() as u32);
+
+			accounts += 1;
+			moved += kitties.len() as u64;
+		}
+
+		StorageVersion::new(2).put::<Pallet<T>>();
+
+		log::info!("migrated {} kitties of {} accounts to storage version 2", moved, accounts);
+
+		T::DbWeight::get().reads_writes(accounts + 1, accounts * 2 + moved + 1)
+	}
+}
+
+//v2 -> v3：dna是否揭示改为记录在 DnaRevealedAt 中，按揭示区块统计等待揭示的数量，并记录每只kitty锁定的押金
+pub mod v3 {
+	use super::*;
+
+	pub fn migrate<T: Config>() -> Weight {
+		let on_chain_version = Pallet::<T>::on_chain_storage_version();
+
+		if on_chain_version >= 3 {
+			log::info!("kitties storage is already at {:?}, skip migration", on_chain_version);
+			return T::DbWeight::get().reads(1)
+		}
+
+		//等待揭示的kitty只有最近 DnaRevealDelay 个区块创建的，数量不多
+		let mut pending = BTreeSet::new();
+		for (reveal_at, kitty_id, _) in PendingDna::<T>::iter() {
+			PendingDnaCount::<T>::mutate(reveal_at, |count| *count = count.saturating_add(1));
+			pending.insert(kitty_id);
+		}
+
+		//已经揭示的kitty无从得知揭示的区块，视为0号区块揭示，销毁时不会没收押金。
+		//v1 迁移时已经记录了从 v0 迁移的kitty的押金，其余kitty创建时都锁定了kitty价格
+		let mut kitties = 0u64;
+		let mut revealed = 0u64;
+		let mut deposits = 0u64;
+		for kitty_id in Kitties::<T>::iter_keys() {
+			kitties += 1;
+			if !pending.contains(&kitty_id) {
+				DnaRevealedAt::<T>::insert(kitty_id, T::BlockNumber::zero());
+				revealed += 1;
+			}
+			if !KittyDeposit::<T>::contains_key(kitty_id) {
+				KittyDeposit::<T>::insert(kitty_id, T::KittyPrice::get());
+				deposits += 1;
+			}
+		}
+
+		StorageVersion::new(3).put::<Pallet<T>>();
+
+		log::info!(
+			"migrated {} revealed and {} pending kitties to storage version 3",
+			revealed,
+			pending.len()
+		);
+
+		let pending = pending.len() as u64;
+		T::DbWeight::get()
+			.reads_writes(kitties * 2 + pending * 2 + 1, revealed + deposits + pending + 1)
+	}
+}
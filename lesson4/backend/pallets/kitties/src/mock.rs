@@ -2,13 +2,13 @@
 use crate as pallet_kitties;
 use frame_support::{
 	parameter_types,
-	traits::{ConstU16, ConstU32, ConstU64},
+	traits::{ConstU16, ConstU32, ConstU64, GenesisBuild},
 };
 
 use sp_core::H256;
 use sp_runtime::{
-	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	testing::{Header, TestSignature, TestXt, UintAuthorityId},
+	traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentityLookup},
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -29,7 +29,7 @@ frame_support::construct_runtime!(
 		//Balance 模块也是引入的，所以也要实现
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		//实现所定义的pallet
-		KittiesModule: pallet_kitties::{Pallet, Call, Storage, Event<T>},
+		KittiesModule: pallet_kitties::{Pallet, Call, Storage, Config<T>, Event<T>, ValidateUnsigned},
 	}
 );
 
@@ -85,6 +85,44 @@ parameter_types! {
 	pub const KittyPrice: u64 = 10;
 }
 
+//链下工作机需要签名交易，测试中用 UintAuthorityId 代替真实的 sr25519 密钥
+type Extrinsic = TestXt<Call, ()>;
+
+pub struct TestAuthId;
+
+impl frame_system::offchain::AppCrypto<UintAuthorityId, TestSignature> for TestAuthId {
+	type RuntimeAppPublic = UintAuthorityId;
+	type GenericSignature = TestSignature;
+	type GenericPublic = UintAuthorityId;
+}
+
+impl frame_system::offchain::SigningTypes for Test {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
+where
+	Call: From<LocalCall>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+	Call: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: Call,
+		_public: UintAuthorityId,
+		_account: u64,
+		nonce: u64,
+	) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (nonce, ())))
+	}
+}
+
 //先实现pallet，runtime中定义的关联类型都必须要
 impl pallet_kitties::Config for Test {
 	type Event = Event;
@@ -94,15 +132,40 @@ impl pallet_kitties::Config for Test {
 	type KittyIndex = u32;
 	type MaxKittyIndex = ConstU32<3>;
 	type KittyPrice = KittyPrice;
+	type BreedCooldown = ConstU64<5>;
+	type MaxChildren = ConstU32<2>;
+	type DnaRevealDelay = ConstU64<2>;
+	type MaxRevealsPerBlock = ConstU32<4>;
+	type BurnCooldown = ConstU64<5>;
+	type MaxAuctionDuration = ConstU64<10>;
+	type MaxAuctionsPerBlock = ConstU32<2>;
+	type MaxLoanDuration = ConstU64<10>;
+	type MaxLoansPerBlock = ConstU32<2>;
+	type MaxNameLength = ConstU32<8>;
+	type MaxMetadataUriLength = ConstU32<32>;
+	type MetadataDepositPerByte = ConstU64<1>;
+	type AuthorityId = TestAuthId;
+	type MaxAuthorities = ConstU32<4>;
+	type UnsignedPriority = ConstU64<100>;
+	type OnKittyTransfer = ();
+	type WeightInfo = ();
 }
 
 // Build genesis storage according to the mock runtime.
 // 如下内容也需要重新构建
 pub fn new_test_ext() -> sp_io::TestExternalities {
+	new_test_ext_with_kitties(vec![])
+}
+
+//带有创世kitty的测试环境，每项为拥有者和dna
+pub fn new_test_ext_with_kitties(kitties: Vec<(u64, [u8; 16])>) -> sp_io::TestExternalities {
 	let mut ts = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	pallet_balances::GenesisConfig::<Test> { balances: vec![(0, 100), (1, 25), (2, 1)] }
 		.assimilate_storage(&mut ts)
 		.unwrap();
+	pallet_kitties::GenesisConfig::<Test> { kitties }
+		.assimilate_storage(&mut ts)
+		.unwrap();
 
 	let mut ext = sp_io::TestExternalities::new(ts);
 	ext.execute_with(|| System::set_block_number(1));
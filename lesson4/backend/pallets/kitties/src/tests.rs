@@ -1,7 +1,14 @@
 use super::*;
 
-use frame_support::{assert_noop, assert_ok};
-use mock::{new_test_ext, Event as TestEvent, KittiesModule, Origin, System, Test};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Currency, Get, Hooks, ReservableCurrency},
+};
+use mock::{
+	new_test_ext, new_test_ext_with_kitties, Event as TestEvent, KittiesModule, Origin,
+	RandomnessCollectiveFlip, System, Test,
+};
+use sp_runtime::traits::Hash;
 use std::ops::Add; //这里引入标准库中的方法进行计算
 
 //构建三个address,开发链有一系列测试账户，其中前两个账户有一定数额的测试余额
@@ -9,6 +16,41 @@ const ALICE: u64 = 0; //100
 const BOB: u64 = 1; //25
 const CHARLIE: u64 = 2; //1
 
+//测试中创建和繁殖kitty时承诺的秘密
+const DNA_SECRET: [u8; 32] = [7u8; 32];
+
+fn commitment() -> <Test as frame_system::Config>::Hash {
+	<Test as frame_system::Config>::Hashing::hash(&DNA_SECRET)
+}
+
+//执行揭示区块的 on_initialize 抽取随机数，再提供秘密揭示kitty的dna
+fn reveal_dna(kitty_id: u32) {
+	let birth = Kitties::<Test>::get(kitty_id).unwrap().birth;
+	KittiesModule::on_initialize(birth + <Test as Config>::DnaRevealDelay::get());
+	if KittiesModule::drawn_dna(kitty_id).is_some() {
+		assert_ok!(KittiesModule::reveal_dna(Origin::signed(ALICE), kitty_id, DNA_SECRET));
+	}
+}
+
+//揭示dna并等到销毁冷却期结束，之后销毁会退还押金
+fn pass_burn_cooldown(kitty_id: u32) {
+	reveal_dna(kitty_id);
+	let revealed_at = KittiesModule::dna_revealed_at(kitty_id).unwrap();
+	System::set_block_number(revealed_at + <Test as Config>::BurnCooldown::get());
+}
+
+//kitty的性别由dna决定，测试中先揭示dna，再直接改写dna以得到确定的性别
+fn set_gender(kitty_id: u32, gender: Gender) {
+	reveal_dna(kitty_id);
+	Kitties::<Test>::mutate(kitty_id, |kitty| {
+		let kitty = kitty.as_mut().unwrap();
+		kitty.dna[0] = match gender {
+			Gender::Male => 0,
+			Gender::Female => 1,
+		};
+	});
+}
+
 #[test]
 fn it_should_work_for_create_kitty() {
 	new_test_ext().execute_with(|| {
@@ -17,7 +59,7 @@ fn it_should_work_for_create_kitty() {
 		let kitty_id = NextKittyId::<Test>::get();
 
 		//断言是否创建成功
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
 
 		//断言next_kitty_id是否可以成功增加
 		assert_eq!(KittiesModule::next_kitty_id(), kitty_id.add(&1));
@@ -46,7 +88,7 @@ fn create_kitty_should_failed_when_not_enough_balance() {
 		let charlie: u64 = CHARLIE;
 
 		assert_noop!(
-			KittiesModule::create(Origin::signed(charlie)),
+			KittiesModule::create(Origin::signed(charlie), commitment()),
 			Error::<Test>::NotEnoughBalance
 		);
 	});
@@ -63,7 +105,10 @@ fn create_kitty_should_failed_when_kitty_id_is_invalid() {
 		NextKittyId::<Test>::set(max_index);
 
 		
-		assert_noop!(KittiesModule::create(Origin::signed(bob)), Error::<Test>::InvalidKittyId);
+		assert_noop!(
+			KittiesModule::create(Origin::signed(bob), commitment()),
+			Error::<Test>::InvalidKittyId
+		);
 	});
 }
 
@@ -74,12 +119,15 @@ fn create_kitty_should_failed_when_own_too_many_kitties() {
 		//因为KittyIndex可以在外部设置，所以我们手动设置一个无效值,我么之前设置了最多能有三个kitties
 		//type MaxKittyIndex = ConstU32<3>;
 		//我们连续创建3个
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
 
 		// 然后断言创建第4个
-		assert_noop!(KittiesModule::create(Origin::signed(alice)), Error::<Test>::OwnTooManyKitties);
+		assert_noop!(
+			KittiesModule::create(Origin::signed(alice), commitment()),
+			Error::<Test>::OwnTooManyKitties
+		);
 	});
 }
 
@@ -89,19 +137,34 @@ fn it_should_work_for_breed_kitty() {
 	new_test_ext().execute_with(|| {
 		let alice: u64 = ALICE;
 		let kitty_id_1 = NextKittyId::<Test>::get();
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
 
 		let kitty_id_2 = NextKittyId::<Test>::get();
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
 
 		let new_kitty_id = NextKittyId::<Test>::get();
 
+		set_gender(kitty_id_1, Gender::Male);
+		set_gender(kitty_id_2, Gender::Female);
+
 		//断言是否创建成功
-		assert_ok!(KittiesModule::breed(Origin::signed(alice), kitty_id_1, kitty_id_2));
+		assert_ok!(KittiesModule::breed(
+			Origin::signed(alice),
+			kitty_id_1,
+			kitty_id_2,
+			0,
+			commitment()
+		));
 
 		//断言新创建的kitty持有人是当前创建者
 		assert_eq!(KittyOwner::<Test>::get(new_kitty_id), Some(alice));
 
+		//断言新kitty记录了父母、代数和出生区块
+		let new_kitty = Kitties::<Test>::get(new_kitty_id).unwrap();
+		assert_eq!(new_kitty.parents, Some((kitty_id_1, kitty_id_2)));
+		assert_eq!(new_kitty.generation, 1);
+		assert_eq!(new_kitty.birth, System::block_number());
+
 		//断言kitty已经存储,assert_ne 和 None 双重否定（即断言成功），为什么要使用这个，因为我们无法判断生成的kitty的具体值，但是可以确定它是有值还是无值，所以就可以使用这种方式来对存在性进行判断
 		assert_ne!(Kitties::<Test>::get(new_kitty_id), None);
 
@@ -129,13 +192,13 @@ fn it_should_fail_when_not_enough_balance() {
 	new_test_ext().execute_with(|| {
 		let bob: u64 = BOB;
 		let kitty_id_1 = NextKittyId::<Test>::get();
-		assert_ok!(KittiesModule::create(Origin::signed(bob)));
+		assert_ok!(KittiesModule::create(Origin::signed(bob), commitment()));
 
 		let kitty_id_2 = NextKittyId::<Test>::get();
-		assert_ok!(KittiesModule::create(Origin::signed(bob)));
+		assert_ok!(KittiesModule::create(Origin::signed(bob), commitment()));
 
 		assert_noop!(
-			KittiesModule::breed(Origin::signed(bob), kitty_id_1, kitty_id_2),
+			KittiesModule::breed(Origin::signed(bob), kitty_id_1, kitty_id_2, 0, commitment()),
 			Error::<Test>::NotEnoughBalance
 		);
 	})
@@ -147,17 +210,17 @@ fn breed_kitty_should_failed_when_kitty_id_is_same() {
 	new_test_ext().execute_with(|| {
 		let alice: u64 = ALICE;
 		let kitty_id_1 = NextKittyId::<Test>::get();
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
 
 		let _kitty_id_2 = NextKittyId::<Test>::get();
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
 
 		//因为KittyIndex可以在外部设置，所以我们手动设置一个无效值
 		// let max_index = <Test as Config>::KittyIndex::max_value();
 		// NextKittyId::<Test>::set(max_index);
 
 		assert_noop!(
-			KittiesModule::breed(Origin::signed(alice), kitty_id_1, kitty_id_1),
+			KittiesModule::breed(Origin::signed(alice), kitty_id_1, kitty_id_1, 0, commitment()),
 			Error::<Test>::SameKittyId
 		);
 	});
@@ -168,16 +231,16 @@ fn breed_kitty_should_failed_when_kitty_id_is_invalid() {
 	new_test_ext().execute_with(|| {
 		let alice: u64 = ALICE;
 		let kitty_id_1 = NextKittyId::<Test>::get();
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
 
 		let _kitty_id_2 = NextKittyId::<Test>::get();
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
 
 		let kitty_id_3 = NextKittyId::<Test>::get();
 		
 
 		assert_noop!(
-			KittiesModule::breed(Origin::signed(alice), kitty_id_1, kitty_id_3),
+			KittiesModule::breed(Origin::signed(alice), kitty_id_1, kitty_id_3, 0, commitment()),
 			Error::<Test>::InvalidKittyId
 		);
 	});
@@ -189,13 +252,13 @@ fn breed_kitty_should_failed_when_own_too_many_kitties() {
 	new_test_ext().execute_with(|| {
 		let alice: u64 = ALICE;
 		let kitty_id_1 = NextKittyId::<Test>::get();
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
 
 		let kitty_id_2 = NextKittyId::<Test>::get();
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
 
 		//再创建一只
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
 
 
 		//断言是否创建成功
@@ -219,7 +282,7 @@ fn it_should_work_for_transfer_kitty() {
 		let kitty_id = NextKittyId::<Test>::get();
 
 		//断言是否创建成功
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
 		assert_ok!(KittiesModule::transfer(Origin::signed(alice), kitty_id,bob));
 
 		//断言next_kitty_id是否可以成功增加
@@ -254,17 +317,61 @@ fn transfer_kitty_fail_when_not_enough_balance() {
 	new_test_ext().execute_with(|| {
 		let alice: u64 = ALICE;
 		let bob: u64 = BOB;
+		let charlie: u64 = CHARLIE;
 
 		//创建两个
-		assert_ok!(KittiesModule::create(Origin::signed(bob)));
+		assert_ok!(KittiesModule::create(Origin::signed(bob), commitment()));
 
 		//获取当前kitty的id,创建时会默认将该id给新的kitty
 		let kitty_id = NextKittyId::<Test>::get();
 
-		assert_ok!(KittiesModule::create(Origin::signed(bob)));
+		assert_ok!(KittiesModule::create(Origin::signed(bob), commitment()));
 
-		assert_noop!(KittiesModule::transfer(Origin::signed(bob),kitty_id,alice),Error::<Test>::NotEnoughBalance);
-		
+		//新的拥有者锁定不起押金
+		assert_noop!(KittiesModule::transfer(Origin::signed(bob),kitty_id,charlie),Error::<Test>::NotEnoughBalance);
+
+		//原拥有者不需要额外的余额，押金由新的拥有者锁定
+		assert_ok!(KittiesModule::transfer(Origin::signed(bob), kitty_id, alice));
+		assert_eq!(
+			<Test as Config>::Currency::reserved_balance(&bob),<Test as Config>::KittyPrice::get()
+		);
+		assert_eq!(
+			<Test as Config>::Currency::reserved_balance(&alice),<Test as Config>::KittyPrice::get()
+		);
+	});
+}
+
+#[test]
+fn transfer_kitty_should_not_change_state_when_metadata_deposit_unaffordable() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::set_kitty_metadata(
+			Origin::signed(alice),
+			kitty_id,
+			b"tomcat".to_vec(),
+			vec![]
+		));
+
+		//bob 还剩15，锁得起kitty押金，但锁不起kitty押金加元数据押金
+		assert_ok!(KittiesModule::create(Origin::signed(bob), commitment()));
+		assert_eq!(<Test as Config>::Currency::free_balance(&bob), 15);
+
+		assert_noop!(
+			KittiesModule::transfer(Origin::signed(alice), kitty_id, bob),
+			Error::<Test>::NotEnoughBalance
+		);
+		assert_noop!(
+			KittiesModule::transfer_from(Origin::signed(alice), alice, bob, kitty_id),
+			Error::<Test>::NotEnoughBalance
+		);
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), Some(alice));
+		assert_eq!(
+			<Test as Config>::Currency::reserved_balance(&alice),
+			<Test as Config>::KittyPrice::get() + 6
+		);
 	});
 }
 
@@ -276,14 +383,14 @@ fn transfer_kitty_fail_when_own_too_many_kitties() {
 		let bob: u64 = BOB;
 
 		//断言是否创建成功
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
-		// assert_ok!(KittiesModule::create(Origin::signed(alice)));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		// assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
 
 		//获取当前kitty的id,创建时会默认将该id给新的kitty
 		let kitty_id = NextKittyId::<Test>::get();
-		assert_ok!(KittiesModule::create(Origin::signed(bob)));
+		assert_ok!(KittiesModule::create(Origin::signed(bob), commitment()));
 
 		// print!("before")
 
@@ -301,9 +408,1862 @@ fn transfer_kitty_fail_when_not_owner() {
 		let kitty_id = NextKittyId::<Test>::get();
 
 		//断言是否创建成功
-		assert_ok!(KittiesModule::create(Origin::signed(alice)));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
 
 		assert_noop!(KittiesModule::transfer(Origin::signed(bob),kitty_id,alice),Error::<Test>::NotOwner);
 		
 	});
-}
\ No newline at end of file
+}
+
+#[test]
+fn it_should_work_for_set_price() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		//挂单出售
+		assert_ok!(KittiesModule::set_price(Origin::signed(alice), kitty_id, 20));
+		assert_eq!(KittiesModule::kitties_for_sale(kitty_id), Some(20));
+
+		//重复挂单会更新价格
+		assert_ok!(KittiesModule::set_price(Origin::signed(alice), kitty_id, 30));
+		assert_eq!(KittiesModule::kitties_for_sale(kitty_id), Some(30));
+
+		System::assert_has_event(TestEvent::KittiesModule(Event::KittyListed(alice, kitty_id, 30)));
+	});
+}
+
+#[test]
+fn set_price_fail_when_not_owner() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		assert_noop!(
+			KittiesModule::set_price(Origin::signed(bob), kitty_id, 20),
+			Error::<Test>::NotOwner
+		);
+	});
+}
+
+#[test]
+fn set_price_fail_when_kitty_id_is_invalid() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let kitty_id = NextKittyId::<Test>::get();
+
+		assert_noop!(
+			KittiesModule::set_price(Origin::signed(alice), kitty_id, 20),
+			Error::<Test>::InvalidKittyId
+		);
+	});
+}
+
+#[test]
+fn it_should_work_for_cancel_sale() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::set_price(Origin::signed(alice), kitty_id, 20));
+
+		assert_ok!(KittiesModule::cancel_sale(Origin::signed(alice), kitty_id));
+		assert_eq!(KittiesModule::kitties_for_sale(kitty_id), None);
+
+		System::assert_has_event(TestEvent::KittiesModule(Event::SaleCancelled(alice, kitty_id)));
+	});
+}
+
+#[test]
+fn cancel_sale_fail_when_not_for_sale() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		assert_noop!(
+			KittiesModule::cancel_sale(Origin::signed(alice), kitty_id),
+			Error::<Test>::NotForSale
+		);
+
+		//只有拥有者可以取消
+		assert_ok!(KittiesModule::set_price(Origin::signed(alice), kitty_id, 20));
+		assert_noop!(
+			KittiesModule::cancel_sale(Origin::signed(bob), kitty_id),
+			Error::<Test>::NotOwner
+		);
+	});
+}
+
+#[test]
+fn it_should_work_for_buy_kitty() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::set_price(Origin::signed(alice), kitty_id, 5));
+
+		assert_ok!(KittiesModule::buy(Origin::signed(bob), kitty_id, 5));
+
+		//所有权转移，挂单失效
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), Some(bob));
+		assert_eq!(KittiesModule::owned_count(alice), 0);
+		assert_eq!(KittiesModule::kitties_of(&bob, None, 10), vec![kitty_id]);
+		assert_eq!(KittiesModule::kitties_for_sale(kitty_id), None);
+
+		//卖家收到价款并释放押金，买家付款并锁定押金
+		let kitty_price = <Test as Config>::KittyPrice::get();
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&alice), 0);
+		assert_eq!(<Test as Config>::Currency::free_balance(&alice), 100 + 5);
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&bob), kitty_price);
+		assert_eq!(<Test as Config>::Currency::free_balance(&bob), 25 - 5 - kitty_price);
+
+		System::assert_has_event(TestEvent::KittiesModule(Event::KittySold(
+			alice, bob, kitty_id, 5,
+		)));
+	});
+}
+
+#[test]
+fn buy_kitty_fail_when_not_for_sale() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		assert_noop!(
+			KittiesModule::buy(Origin::signed(bob), kitty_id, 5),
+			Error::<Test>::NotForSale
+		);
+	});
+}
+
+#[test]
+fn buy_kitty_fail_when_buy_own_kitty() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::set_price(Origin::signed(alice), kitty_id, 5));
+
+		assert_noop!(
+			KittiesModule::buy(Origin::signed(alice), kitty_id, 5),
+			Error::<Test>::BuyOwnKitty
+		);
+	});
+}
+
+#[test]
+fn buy_kitty_fail_when_not_enough_balance() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let charlie: u64 = CHARLIE;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		//bob 付得起价款，但付不起押金
+		assert_ok!(KittiesModule::set_price(Origin::signed(alice), kitty_id, 20));
+		assert_noop!(
+			KittiesModule::buy(Origin::signed(bob), kitty_id, 20),
+			Error::<Test>::NotEnoughBalance
+		);
+
+		assert_ok!(KittiesModule::set_price(Origin::signed(alice), kitty_id, 1));
+		assert_noop!(
+			KittiesModule::buy(Origin::signed(charlie), kitty_id, 1),
+			Error::<Test>::NotEnoughBalance
+		);
+	});
+}
+
+#[test]
+fn buy_kitty_fail_when_price_raised() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::set_price(Origin::signed(alice), kitty_id, 5));
+
+		//bob 按5购买，交易上链前卖家把价格抬高到10
+		assert_ok!(KittiesModule::set_price(Origin::signed(alice), kitty_id, 10));
+		assert_noop!(
+			KittiesModule::buy(Origin::signed(bob), kitty_id, 5),
+			Error::<Test>::PriceTooHigh
+		);
+
+		//价格不高于 max_price 时按挂单价格成交
+		assert_ok!(KittiesModule::set_price(Origin::signed(alice), kitty_id, 3));
+		assert_ok!(KittiesModule::buy(Origin::signed(bob), kitty_id, 5));
+		assert_eq!(<Test as Config>::Currency::free_balance(&alice), 100 + 3);
+		System::assert_has_event(TestEvent::KittiesModule(Event::KittySold(
+			alice, bob, kitty_id, 3,
+		)));
+	});
+}
+
+#[test]
+fn transfer_kitty_should_cancel_sale() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::set_price(Origin::signed(alice), kitty_id, 5));
+
+		assert_ok!(KittiesModule::transfer(Origin::signed(alice), kitty_id, bob));
+
+		assert_eq!(KittiesModule::kitties_for_sale(kitty_id), None);
+	});
+}
+
+#[test]
+fn it_should_work_for_burn_kitty() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_eq!(KittiesModule::total_supply(), 2);
+
+		pass_burn_cooldown(kitty_id);
+		assert_ok!(KittiesModule::burn(Origin::signed(alice), kitty_id));
+
+		//kitty及所有权信息被移除
+		assert_eq!(Kitties::<Test>::get(kitty_id), None);
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), None);
+		assert!(!KittiesModule::owns(&alice, kitty_id));
+		assert_eq!(KittiesModule::owned_count(alice), 1);
+
+		//押金被释放，id不会被复用
+		assert_eq!(
+			<Test as Config>::Currency::reserved_balance(&alice),
+			<Test as Config>::KittyPrice::get()
+		);
+		assert_eq!(KittiesModule::next_kitty_id(), kitty_id.add(&2));
+		assert_eq!(KittiesModule::total_supply(), 1);
+
+		System::assert_has_event(TestEvent::KittiesModule(Event::KittyBurned(alice, kitty_id)));
+	});
+}
+
+#[test]
+fn burn_kitty_fail_when_not_owner() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		assert_noop!(KittiesModule::burn(Origin::signed(bob), kitty_id), Error::<Test>::NotOwner);
+		assert_noop!(
+			KittiesModule::burn(Origin::signed(alice), kitty_id.add(&1)),
+			Error::<Test>::InvalidKittyId
+		);
+	});
+}
+
+#[test]
+fn breed_kitty_should_failed_when_same_gender() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let kitty_id_1 = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		let kitty_id_2 = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		set_gender(kitty_id_1, Gender::Female);
+		set_gender(kitty_id_2, Gender::Female);
+
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(alice), kitty_id_1, kitty_id_2, 0, commitment()),
+			Error::<Test>::SameGender
+		);
+	});
+}
+
+#[test]
+fn created_kitty_should_be_generation_zero() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(ALICE), commitment()));
+
+		let kitty = Kitties::<Test>::get(kitty_id).unwrap();
+		assert_eq!(kitty.parents, None);
+		assert_eq!(kitty.generation, 0);
+		assert_eq!(kitty.birth, System::block_number());
+	});
+}
+
+#[test]
+fn gender_should_be_decoded_from_dna() {
+	let mut kitty: KittyOf<Test> =
+		Kitty { dna: [0u8; 16], asset: 0, parents: None, generation: 0, birth: 0 };
+	assert_eq!(kitty.gender(), Gender::Male);
+
+	kitty.dna[0] = 0b1011;
+	assert_eq!(kitty.gender(), Gender::Female);
+}
+
+#[test]
+fn dna_should_be_revealed_after_delay() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		//创建时dna还没有确定
+		assert_eq!(Kitties::<Test>::get(0).unwrap().dna, [0u8; 16]);
+		assert!(!KittiesModule::is_dna_revealed(0));
+		assert_eq!(KittiesModule::pending_dna(3, 0), Some(DnaSource::Created));
+
+		//没到揭示区块不会抽取随机数，也不能揭示
+		KittiesModule::on_initialize(2);
+		assert_eq!(KittiesModule::drawn_dna(0), None);
+		assert_noop!(
+			KittiesModule::reveal_dna(Origin::signed(alice), 0, DNA_SECRET),
+			Error::<Test>::DnaSeedNotDrawn
+		);
+
+		//揭示区块抽取随机数，等待创建者提供秘密
+		System::set_block_number(3);
+		KittiesModule::on_initialize(3);
+		assert!(KittiesModule::drawn_dna(0).is_some());
+		assert!(!KittiesModule::is_dna_revealed(0));
+		assert_eq!(KittiesModule::pending_dna(3, 0), None);
+		assert_eq!(KittiesModule::pending_dna_count(3), 0);
+		System::assert_has_event(TestEvent::KittiesModule(Event::DnaSeedDrawn(0)));
+
+		//秘密和承诺不符时不能揭示
+		assert_noop!(
+			KittiesModule::reveal_dna(Origin::signed(alice), 0, [0u8; 32]),
+			Error::<Test>::WrongDnaSecret
+		);
+
+		//知道秘密的任何账户都可以揭示
+		System::set_block_number(4);
+		assert_ok!(KittiesModule::reveal_dna(Origin::signed(BOB), 0, DNA_SECRET));
+		let dna = Kitties::<Test>::get(0).unwrap().dna;
+		assert_ne!(dna, [0u8; 16]);
+		assert!(KittiesModule::is_dna_revealed(0));
+		assert_eq!(KittiesModule::dna_revealed_at(0), Some(4));
+		assert_eq!(KittiesModule::drawn_dna(0), None);
+		assert_eq!(KittiesModule::dna_commitment(0), None);
+		System::assert_has_event(TestEvent::KittiesModule(Event::DnaRevealed(0, dna)));
+
+		//dna揭示之前不能销毁
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_noop!(KittiesModule::burn(Origin::signed(alice), 1), Error::<Test>::DnaNotRevealed);
+	});
+}
+
+#[test]
+fn breed_kitty_should_failed_when_dna_not_revealed() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(alice), 0, 1, 0, commitment()),
+			Error::<Test>::DnaNotRevealed
+		);
+
+		//繁殖出来的kitty同样要等到揭示区块
+		set_gender(0, Gender::Male);
+		set_gender(1, Gender::Female);
+		assert_ok!(KittiesModule::breed(Origin::signed(alice), 0, 1, 0, commitment()));
+		assert!(!KittiesModule::is_dna_revealed(2));
+		assert_eq!(
+			KittiesModule::pending_dna(3, 2),
+			Some(DnaSource::Bred(
+				Kitties::<Test>::get(0).unwrap().dna,
+				Kitties::<Test>::get(1).unwrap().dna
+			))
+		);
+	});
+}
+
+//在新的环境中由creator用secret创建kitty，揭示区块的父区块哈希为parent_hash，返回揭示后的dna
+fn revealed_dna(creator: u64, secret: [u8; 32], parent_hash: sp_core::H256) -> [u8; 16] {
+	new_test_ext().execute_with(|| {
+		let dna_commitment = <Test as frame_system::Config>::Hashing::hash(&secret);
+		assert_ok!(KittiesModule::create(Origin::signed(creator), dna_commitment));
+		assert_eq!(Kitties::<Test>::get(0).unwrap().dna, [0u8; 16]);
+
+		let reveal_at = 1 + <Test as Config>::DnaRevealDelay::get();
+		System::initialize(&reveal_at, &parent_hash, &Default::default());
+		RandomnessCollectiveFlip::on_initialize(reveal_at);
+		KittiesModule::on_initialize(reveal_at);
+		assert_ok!(KittiesModule::reveal_dna(Origin::signed(creator), 0, secret));
+
+		Kitties::<Test>::get(0).unwrap().dna
+	})
+}
+
+#[test]
+fn creator_should_not_be_able_to_grind_dna() {
+	let hash_1 = sp_core::H256::repeat_byte(1);
+	let hash_2 = sp_core::H256::repeat_byte(2);
+
+	//dna与创建者和创建时的状态无关，换账户重试得到的是同一个dna
+	assert_eq!(revealed_dna(ALICE, DNA_SECRET, hash_1), revealed_dna(BOB, DNA_SECRET, hash_1));
+
+	//dna还取决于创建之后才产生的区块
+	assert_ne!(revealed_dna(ALICE, DNA_SECRET, hash_1), revealed_dna(ALICE, DNA_SECRET, hash_2));
+}
+
+#[test]
+fn block_author_should_not_be_able_to_grind_dna() {
+	use codec::Encode;
+
+	//出块者把揭示区块的父区块哈希固定为对自己有利的值，随机数完全由出块者决定
+	let biased = sp_core::H256::repeat_byte(0xff);
+
+	//出块者不知道创建者的秘密，同一个随机数对不同的秘密得到不同的dna，无法预先挑选
+	let dna_1 = revealed_dna(ALICE, [1u8; 32], biased);
+	let dna_2 = revealed_dna(ALICE, [2u8; 32], biased);
+	assert_ne!(dna_1, dna_2);
+
+	//只用随机数时出块者可以直接算出dna，混合秘密之后的dna和它不同
+	let (seed, _) = new_test_ext().execute_with(|| {
+		let reveal_at = 1 + <Test as Config>::DnaRevealDelay::get();
+		System::initialize(&reveal_at, &biased, &Default::default());
+		RandomnessCollectiveFlip::on_initialize(reveal_at);
+		<RandomnessCollectiveFlip as frame_support::traits::Randomness<_, _>>::random(
+			&(&b"kitty_pallet::dna"[..], 0u32).encode(),
+		)
+	});
+	let seed_only = (seed, 0u32).using_encoded(sp_io::hashing::blake2_128);
+	assert_ne!(dna_1, seed_only);
+	assert_ne!(dna_2, seed_only);
+}
+
+#[test]
+fn burning_to_reroll_dna_should_forfeit_deposit() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let price = <Test as Config>::KittyPrice::get();
+		let free = <Test as Config>::Currency::free_balance(&alice);
+
+		//创建后马上销毁来重新挑选dna：揭示之前不能销毁
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_noop!(KittiesModule::burn(Origin::signed(alice), 0), Error::<Test>::DnaNotRevealed);
+
+		//揭示之后不满意dna，冷却期内销毁会没收押金
+		reveal_dna(0);
+		assert_ok!(KittiesModule::burn(Origin::signed(alice), 0));
+		System::assert_has_event(TestEvent::KittiesModule(Event::DepositForfeited(
+			alice, 0, price,
+		)));
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&alice), 0);
+		assert_eq!(<Test as Config>::Currency::free_balance(&alice), free - price);
+
+		//重新创建的kitty同样要等到揭示区块，每挑选一次都要损失一份押金
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert!(!KittiesModule::is_dna_revealed(1));
+		reveal_dna(1);
+		assert_ok!(KittiesModule::burn(Origin::signed(alice), 1));
+		assert_eq!(<Test as Config>::Currency::free_balance(&alice), free - price * 2);
+
+		//冷却期结束后销毁退还押金
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		pass_burn_cooldown(2);
+		assert_ok!(KittiesModule::burn(Origin::signed(alice), 2));
+		assert_eq!(<Test as Config>::Currency::free_balance(&alice), free - price * 2);
+	});
+}
+
+#[test]
+fn create_kitty_should_fail_when_too_many_reveals() {
+	new_test_ext().execute_with(|| {
+		//alice 和 bob 在同一个区块创建的kitty超过每个区块的揭示上限
+		for _ in 0..3 {
+			assert_ok!(KittiesModule::create(Origin::signed(ALICE), commitment()));
+		}
+		assert_ok!(KittiesModule::create(Origin::signed(BOB), commitment()));
+		assert_eq!(KittiesModule::pending_dna_count(3), 4);
+
+		assert_noop!(
+			KittiesModule::create(Origin::signed(BOB), commitment()),
+			Error::<Test>::TooManyReveals
+		);
+
+		//下一个区块创建的kitty在下一个区块揭示
+		System::set_block_number(2);
+		assert_ok!(KittiesModule::create(Origin::signed(BOB), commitment()));
+		assert_eq!(KittiesModule::pending_dna_count(4), 1);
+
+		KittiesModule::on_initialize(3);
+		assert_eq!(KittiesModule::pending_dna_count(3), 0);
+		assert!((0..4).all(|kitty_id| KittiesModule::drawn_dna(kitty_id).is_some()));
+		assert_eq!(KittiesModule::drawn_dna(4), None);
+	});
+}
+
+#[test]
+fn mix_dna_should_be_deterministic_and_respect_parent_order() {
+	let dna_1 = [0xffu8; 16];
+	let dna_2 = [0x00u8; 16];
+	let selector = [0xf0u8; 16];
+
+	let child = KittiesModule::mix_dna(&dna_1, &dna_2, &selector);
+
+	//相同输入得到相同输出
+	assert_eq!(child, KittiesModule::mix_dna(&dna_1, &dna_2, &selector));
+
+	//选择子为1的位来自kitty_1，为0的位来自kitty_2，突变最多翻转每个字节的一位
+	for i in 0..child.len() {
+		assert!((child[i] ^ 0xf0).count_ones() <= 1);
+	}
+
+	//交换父母顺序后，基因来源随之交换
+	let swapped = KittiesModule::mix_dna(&dna_2, &dna_1, &selector);
+	for i in 0..swapped.len() {
+		assert!((swapped[i] ^ 0x0f).count_ones() <= 1);
+	}
+}
+
+#[test]
+fn migration_to_v1_should_work() {
+	use frame_support::{
+		storage::unhashed,
+		traits::{GetStorageVersion, StorageVersion},
+	};
+
+	new_test_ext().execute_with(|| {
+		let old = migrations::v1::OldKitty { dna: [7u8; 16], asset: 100 };
+		unhashed::put(&Kitties::<Test>::hashed_key_for(0), &old);
+		StorageVersion::new(0).put::<KittiesModule>();
+
+		migrations::v1::migrate::<Test>();
+
+		assert_eq!(KittiesModule::on_chain_storage_version(), 1);
+		assert_eq!(
+			Kitties::<Test>::get(0),
+			Some(Kitty { dna: [7u8; 16], asset: 100, parents: None, generation: 0, birth: 0 })
+		);
+	});
+}
+
+#[test]
+fn migration_from_lesson2_layout_should_work() {
+	use codec::Encode;
+	use frame_support::{
+		storage::{migration::put_storage_value, unhashed},
+		traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		Blake2_128Concat, StorageHasher,
+	};
+
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+
+		//lesson2 的存储：kitty只有dna，拥有者名下的kitty保存在 AllKitties 中
+		unhashed::put(&Kitties::<Test>::hashed_key_for(0), &migrations::v1::LegacyKitty([7u8; 16]));
+		unhashed::put(
+			&Kitties::<Test>::hashed_key_for(1),
+			&migrations::v1::OldKitty { dna: [8u8; 16], asset: 100 },
+		);
+		KittyOwner::<Test>::insert(0, alice);
+		KittyOwner::<Test>::insert(1, alice);
+		NextKittyId::<Test>::put(2);
+		put_storage_value(
+			b"KittiesModule",
+			migrations::v2::OLD_ALL_KITTIES,
+			&Blake2_128Concat::hash(&alice.encode()),
+			vec![0u32, 1],
+		);
+		StorageVersion::new(0).put::<KittiesModule>();
+
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(migrations::Migration::<Test>::pre_upgrade());
+
+		migrations::Migration::<Test>::on_runtime_upgrade();
+
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(migrations::Migration::<Test>::post_upgrade());
+
+		assert_eq!(KittiesModule::on_chain_storage_version(), 3);
+		assert_eq!(
+			Kitties::<Test>::get(0),
+			Some(Kitty { dna: [7u8; 16], asset: 0, parents: None, generation: 0, birth: 0 })
+		);
+		assert_eq!(
+			Kitties::<Test>::get(1),
+			Some(Kitty { dna: [8u8; 16], asset: 100, parents: None, generation: 0, birth: 0 })
+		);
+		assert_eq!(KittiesModule::owned_count(alice), 2);
+		assert!(KittiesModule::is_dna_revealed(0) && KittiesModule::is_dna_revealed(1));
+
+		//lesson2 和 lesson4 的kitty都锁定了kitty价格
+		assert_eq!(KittiesModule::kitty_deposit(0), <Test as Config>::KittyPrice::get());
+		assert_eq!(KittiesModule::kitty_deposit(1), <Test as Config>::KittyPrice::get());
+
+		//已经是最新版本时不会重复迁移
+		migrations::Migration::<Test>::on_runtime_upgrade();
+		assert_eq!(Kitties::<Test>::get(0).unwrap().dna, [7u8; 16]);
+	});
+}
+
+#[test]
+fn migration_to_v2_should_work() {
+	use codec::Encode;
+	use frame_support::{
+		storage::migration::put_storage_value,
+		traits::{GetStorageVersion, StorageVersion},
+		Blake2_128Concat, StorageHasher,
+	};
+
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		put_storage_value(
+			b"KittiesModule",
+			migrations::v2::OLD_ALL_KITTIES,
+			&Blake2_128Concat::hash(&alice.encode()),
+			vec![0u32, 2],
+		);
+		put_storage_value(
+			b"KittiesModule",
+			migrations::v2::OLD_ALL_KITTIES,
+			&Blake2_128Concat::hash(&bob.encode()),
+			vec![1u32],
+		);
+		StorageVersion::new(1).put::<KittiesModule>();
+
+		migrations::v2::migrate::<Test>();
+
+		assert_eq!(KittiesModule::on_chain_storage_version(), 2);
+		assert_eq!(KittiesModule::owned_count(alice), 2);
+		assert_eq!(KittiesModule::owned_count(bob), 1);
+		assert!(KittiesModule::owns(&alice, 0) && KittiesModule::owns(&alice, 2));
+		assert!(KittiesModule::owns(&bob, 1));
+
+		//旧的存储项已经被删除
+		assert!(frame_support::storage::migration::get_storage_value::<Vec<u32>>(
+			b"KittiesModule",
+			migrations::v2::OLD_ALL_KITTIES,
+			&Blake2_128Concat::hash(&alice.encode()),
+		)
+		.is_none());
+	});
+}
+
+#[test]
+fn migration_to_v3_should_work() {
+	use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		//kitty 0 的dna已经揭示，kitty 1 在3号区块揭示
+		let kitty: KittyOf<Test> =
+			Kitty { dna: [7u8; 16], asset: 0, parents: None, generation: 0, birth: 0 };
+		Kitties::<Test>::insert(0, &kitty);
+		Kitties::<Test>::insert(1, Kitty { dna: [0u8; 16], birth: 1, ..kitty });
+		PendingDna::<Test>::insert(3, 1, DnaSource::Created);
+		StorageVersion::new(2).put::<KittiesModule>();
+
+		migrations::v3::migrate::<Test>();
+
+		assert_eq!(KittiesModule::on_chain_storage_version(), 3);
+		assert_eq!(KittiesModule::dna_revealed_at(0), Some(0));
+		assert!(!KittiesModule::is_dna_revealed(1));
+		assert_eq!(KittiesModule::pending_dna_count(3), 1);
+
+		//v1 之后创建的kitty都锁定了kitty价格
+		assert_eq!(KittiesModule::kitty_deposit(0), <Test as Config>::KittyPrice::get());
+
+		//升级前等待揭示的kitty没有承诺，到期直接揭示
+		KittiesModule::on_initialize(3);
+		assert_eq!(KittiesModule::dna_revealed_at(1), Some(3));
+	});
+}
+
+#[test]
+fn kitties_of_should_be_paginated() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		for _ in 0..3 {
+			assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		}
+
+		//每页2个，用上一页的最后一个id查询下一页
+		let first = KittiesModule::kitties_of(&alice, None, 2);
+		assert_eq!(first.len(), 2);
+		let second = KittiesModule::kitties_of(&alice, first.last().copied(), 2);
+		assert_eq!(second.len(), 1);
+
+		let mut all = [first, second].concat();
+		all.sort();
+		assert_eq!(all, vec![0, 1, 2]);
+
+		assert!(KittiesModule::kitties_of(&BOB, None, 2).is_empty());
+	});
+}
+
+#[test]
+fn breed_kitty_should_failed_when_not_ready() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let kitty_id_1 = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		let kitty_id_2 = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		set_gender(kitty_id_1, Gender::Male);
+		set_gender(kitty_id_2, Gender::Female);
+
+		assert_ok!(KittiesModule::breed(
+			Origin::signed(alice),
+			kitty_id_1,
+			kitty_id_2,
+			0,
+			commitment()
+		));
+
+		//父母进入冷却期
+		let ready_at = System::block_number() + <Test as Config>::BreedCooldown::get();
+		assert_eq!(KittiesModule::ready_at(kitty_id_1), ready_at);
+		assert_eq!(KittiesModule::ready_at(kitty_id_2), ready_at);
+		assert!(!KittiesModule::is_ready_to_breed(kitty_id_1));
+		assert_eq!(KittiesModule::children_count(kitty_id_1), 1);
+
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(alice), kitty_id_1, kitty_id_2, 0, commitment()),
+			Error::<Test>::KittyNotReady
+		);
+
+		//冷却期结束
+		System::set_block_number(ready_at);
+		assert!(KittiesModule::is_ready_to_breed(kitty_id_1));
+	});
+}
+
+#[test]
+fn breed_kitty_should_failed_when_too_many_children() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let kitty_id_1 = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		let kitty_id_2 = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		set_gender(kitty_id_1, Gender::Male);
+		set_gender(kitty_id_2, Gender::Female);
+
+		ChildrenCount::<Test>::insert(kitty_id_1, <Test as Config>::MaxChildren::get());
+
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(alice), kitty_id_1, kitty_id_2, 0, commitment()),
+			Error::<Test>::TooManyChildren
+		);
+	});
+}
+
+#[test]
+fn it_should_work_for_breed_with_siring_offer() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let sire_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		let matron_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(bob), commitment()));
+
+		set_gender(sire_id, Gender::Male);
+		set_gender(matron_id, Gender::Female);
+
+		//alice 提供配种服务
+		assert_ok!(KittiesModule::offer_siring(Origin::signed(alice), sire_id, 3));
+		assert_eq!(KittiesModule::siring_offers(sire_id), Some(3));
+		System::assert_has_event(TestEvent::KittiesModule(Event::SiringOffered(alice, sire_id, 3)));
+
+		let new_kitty_id = NextKittyId::<Test>::get();
+		let alice_free = <Test as Config>::Currency::free_balance(&alice);
+		let bob_free = <Test as Config>::Currency::free_balance(&bob);
+
+		assert_ok!(KittiesModule::breed(Origin::signed(bob), sire_id, matron_id, 3, commitment()));
+
+		//新kitty属于bob，alice收到配种费用
+		assert_eq!(KittyOwner::<Test>::get(new_kitty_id), Some(bob));
+		assert_eq!(KittyOwner::<Test>::get(sire_id), Some(alice));
+		assert_eq!(<Test as Config>::Currency::free_balance(&alice), alice_free + 3);
+		assert_eq!(
+			<Test as Config>::Currency::free_balance(&bob),
+			bob_free - 3 - <Test as Config>::KittyPrice::get()
+		);
+
+		System::assert_has_event(TestEvent::KittiesModule(Event::SiringFeePaid(
+			bob, alice, sire_id, 3,
+		)));
+	});
+}
+
+#[test]
+fn breed_kitty_should_failed_when_siring_fee_raised() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let sire_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		let matron_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(bob), commitment()));
+
+		set_gender(sire_id, Gender::Male);
+		set_gender(matron_id, Gender::Female);
+
+		//bob 按费用3繁殖，交易上链前 alice 把费用提高到5
+		assert_ok!(KittiesModule::offer_siring(Origin::signed(alice), sire_id, 3));
+		assert_ok!(KittiesModule::offer_siring(Origin::signed(alice), sire_id, 5));
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(bob), sire_id, matron_id, 3, commitment()),
+			Error::<Test>::SiringFeeTooHigh
+		);
+
+		//费用不超过上限时按当前费用支付
+		let alice_free = <Test as Config>::Currency::free_balance(&alice);
+		assert_ok!(KittiesModule::breed(Origin::signed(bob), sire_id, matron_id, 5, commitment()));
+		assert_eq!(<Test as Config>::Currency::free_balance(&alice), alice_free + 5);
+	});
+}
+
+#[test]
+fn breed_kitty_should_failed_when_not_owner_and_not_offered() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let sire_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		let matron_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(bob), commitment()));
+
+		set_gender(sire_id, Gender::Male);
+		set_gender(matron_id, Gender::Female);
+
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(bob), sire_id, matron_id, 3, commitment()),
+			Error::<Test>::NotOwner
+		);
+
+		//取消配种服务后同样不能繁殖
+		assert_ok!(KittiesModule::offer_siring(Origin::signed(alice), sire_id, 3));
+		assert_ok!(KittiesModule::cancel_siring(Origin::signed(alice), sire_id));
+		System::assert_has_event(TestEvent::KittiesModule(Event::SiringCancelled(alice, sire_id)));
+
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(bob), sire_id, matron_id, 3, commitment()),
+			Error::<Test>::NotOwner
+		);
+	});
+}
+
+#[test]
+fn offer_siring_fail_when_not_owner() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		assert_noop!(
+			KittiesModule::offer_siring(Origin::signed(bob), kitty_id, 3),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			KittiesModule::cancel_siring(Origin::signed(alice), kitty_id),
+			Error::<Test>::NotOfferedForSiring
+		);
+	});
+}
+
+#[test]
+fn it_should_work_for_transfer_from_with_approval() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let charlie: u64 = CHARLIE;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		//charlie 被授权转移这只kitty
+		assert_ok!(KittiesModule::approve(Origin::signed(alice), kitty_id, Some(charlie)));
+		assert_eq!(KittiesModule::kitty_approvals(kitty_id), Some(charlie));
+		System::assert_has_event(TestEvent::KittiesModule(Event::Approval(
+			alice,
+			Some(charlie),
+			kitty_id,
+		)));
+
+		assert_ok!(KittiesModule::transfer_from(Origin::signed(charlie), alice, bob, kitty_id));
+
+		//所有权转移，授权失效
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), Some(bob));
+		assert_eq!(KittiesModule::kitty_approvals(kitty_id), None);
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&alice), 0);
+		assert_eq!(
+			<Test as Config>::Currency::reserved_balance(&bob),
+			<Test as Config>::KittyPrice::get()
+		);
+
+		System::assert_has_event(TestEvent::KittiesModule(Event::KittyTransferred(
+			alice, bob, kitty_id,
+		)));
+	});
+}
+
+#[test]
+fn it_should_work_for_transfer_from_with_operator() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let charlie: u64 = CHARLIE;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		assert_ok!(KittiesModule::set_approval_for_all(Origin::signed(alice), charlie, true));
+		assert!(KittiesModule::operator_approvals(alice, charlie));
+		System::assert_has_event(TestEvent::KittiesModule(Event::ApprovalForAll(
+			alice, charlie, true,
+		)));
+
+		//操作员可以授权单只kitty，也可以直接转移
+		assert_ok!(KittiesModule::approve(Origin::signed(charlie), kitty_id, Some(bob)));
+		assert_ok!(KittiesModule::transfer_from(Origin::signed(charlie), alice, bob, kitty_id));
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), Some(bob));
+
+		//取消操作员授权
+		assert_ok!(KittiesModule::set_approval_for_all(Origin::signed(alice), charlie, false));
+		assert!(!KittiesModule::operator_approvals(alice, charlie));
+	});
+}
+
+#[test]
+fn transfer_from_fail_when_not_approved() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let charlie: u64 = CHARLIE;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		assert_noop!(
+			KittiesModule::transfer_from(Origin::signed(charlie), alice, bob, kitty_id),
+			Error::<Test>::NotApproved
+		);
+
+		//from 不是拥有者
+		assert_noop!(
+			KittiesModule::transfer_from(Origin::signed(bob), bob, charlie, kitty_id),
+			Error::<Test>::NotOwner
+		);
+
+		//只有拥有者或操作员可以授权
+		assert_noop!(
+			KittiesModule::approve(Origin::signed(charlie), kitty_id, Some(charlie)),
+			Error::<Test>::NotOwner
+		);
+
+		//取消授权后不能再转移
+		assert_ok!(KittiesModule::approve(Origin::signed(alice), kitty_id, Some(charlie)));
+		assert_ok!(KittiesModule::approve(Origin::signed(alice), kitty_id, None));
+		assert_noop!(
+			KittiesModule::transfer_from(Origin::signed(charlie), alice, bob, kitty_id),
+			Error::<Test>::NotApproved
+		);
+	});
+}
+
+#[test]
+fn it_should_work_for_runtime_api_queries() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		let mut kitties = KittiesModule::kitties_of(&alice, None, 10);
+		kitties.sort();
+		assert_eq!(kitties, vec![kitty_id, kitty_id.add(&1)]);
+		assert_eq!(KittiesModule::owned_count(alice), 2);
+		assert_eq!(KittiesModule::total_supply(), 2);
+
+		let kitty = Kitties::<Test>::get(kitty_id).unwrap();
+		assert_eq!(
+			KittiesModule::kitty_details(kitty_id),
+			Some(KittyInfo {
+				owner: alice,
+				gender: kitty.gender(),
+				kitty,
+				name: vec![],
+				metadata_uri: vec![]
+			})
+		);
+		assert_eq!(KittiesModule::kitty_details(kitty_id.add(&2)), None);
+	});
+}
+
+#[test]
+fn asset_should_be_computed_from_dna() {
+	let dna = [42u8; 16];
+	let asset = KittiesModule::asset_from_dna(&dna);
+
+	//相同dna得到相同的资源编号，0保留给还未计算的kitty
+	assert_eq!(asset, KittiesModule::asset_from_dna(&dna));
+	assert!(asset > 0);
+}
+
+#[test]
+fn offchain_worker_should_queue_every_kitty_and_back_off() {
+	use sp_core::offchain::{
+		testing::{TestOffchainExt, TestTransactionPoolExt},
+		OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+	};
+	use sp_runtime::testing::UintAuthorityId;
+
+	let mut ext = new_test_ext();
+	let (offchain, _state) = TestOffchainExt::with_offchain_db(ext.offchain_db());
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	//同一个区块中创建两只kitty
+	ext.execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(ALICE), commitment()));
+		assert_ok!(KittiesModule::create(Origin::signed(ALICE), commitment()));
+	});
+	ext.persist_offchain_overlay();
+
+	ext.execute_with(|| {
+		UintAuthorityId::set_all_keys(vec![11]);
+
+		//dna揭示之前不会提交
+		KittiesModule::offchain_worker(1);
+		assert_eq!(pool_state.read().transactions.len(), 0);
+
+		//两只kitty的dna在第3个区块揭示后都被提交
+		System::set_block_number(3);
+		KittiesModule::on_initialize(3);
+		for kitty_id in 0..2 {
+			assert_ok!(KittiesModule::reveal_dna(Origin::signed(ALICE), kitty_id, DNA_SECRET));
+		}
+		KittiesModule::offchain_worker(3);
+		assert_eq!(pool_state.read().transactions.len(), 2);
+
+		//退避期内不会重复提交
+		KittiesModule::offchain_worker(4);
+		assert_eq!(pool_state.read().transactions.len(), 2);
+
+		//第一只kitty的资源编号已经上链，退避期后只重新提交第二只
+		Kitties::<Test>::mutate(0, |kitty| {
+			let kitty = kitty.as_mut().unwrap();
+			kitty.asset = KittiesModule::asset_from_dna(&kitty.dna);
+		});
+		System::set_block_number(5);
+		KittiesModule::offchain_worker(5);
+		assert_eq!(pool_state.read().transactions.len(), 3);
+	});
+}
+
+#[test]
+fn offchain_worker_should_scan_limited_kitty_ids_per_run() {
+	use sp_core::offchain::{testing::TestOffchainExt, OffchainDbExt, OffchainWorkerExt};
+	use sp_runtime::offchain::storage::StorageValueRef;
+
+	let mut ext = new_test_ext();
+	let (offchain, _state) = TestOffchainExt::with_offchain_db(ext.offchain_db());
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+
+	ext.execute_with(|| {
+		let cursor = || {
+			StorageValueRef::persistent(b"kitty_pallet::cursor").get::<u32>().unwrap().unwrap()
+		};
+		NextKittyId::<Test>::put(100);
+
+		//每次最多扫描64个id，剩下的下次从游标继续
+		KittiesModule::offchain_worker(1);
+		assert_eq!(cursor(), 64);
+
+		System::set_block_number(2);
+		KittiesModule::offchain_worker(2);
+		assert_eq!(cursor(), 100);
+	});
+}
+
+#[test]
+fn it_should_work_for_update_kitty_by_authority() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let charlie: u64 = CHARLIE;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		//只有root可以添加授权账户
+		assert_noop!(
+			KittiesModule::add_authority(Origin::signed(alice), charlie),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(KittiesModule::add_authority(Origin::root(), charlie));
+		assert_noop!(
+			KittiesModule::add_authority(Origin::root(), charlie),
+			Error::<Test>::AlreadyAuthority
+		);
+		System::assert_has_event(TestEvent::KittiesModule(Event::AuthorityAdded(charlie)));
+
+		assert_ok!(KittiesModule::update_kitty(Origin::signed(charlie), kitty_id, 7, None));
+		assert_eq!(Kitties::<Test>::get(kitty_id).unwrap().asset, 7);
+		System::assert_has_event(TestEvent::KittiesModule(Event::KittyUpdated(
+			charlie, kitty_id, 7,
+		)));
+
+		//移除后不能再更新
+		assert_ok!(KittiesModule::remove_authority(Origin::root(), charlie));
+		assert_noop!(
+			KittiesModule::update_kitty(Origin::signed(charlie), kitty_id, 8, None),
+			Error::<Test>::NotAuthority
+		);
+	});
+}
+
+#[test]
+fn update_kitty_fail_when_not_authority() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		assert_noop!(
+			KittiesModule::update_kitty(Origin::signed(alice), kitty_id, 7, None),
+			Error::<Test>::NotAuthority
+		);
+		assert_noop!(
+			KittiesModule::remove_authority(Origin::root(), alice),
+			Error::<Test>::NotAuthority
+		);
+	});
+}
+
+#[test]
+fn add_authority_fail_when_too_many_authorities() {
+	new_test_ext().execute_with(|| {
+		let max = <Test as Config>::MaxAuthorities::get() as u64;
+		for authority in 0..max {
+			assert_ok!(KittiesModule::add_authority(Origin::root(), authority));
+		}
+
+		assert_noop!(
+			KittiesModule::add_authority(Origin::root(), max),
+			Error::<Test>::TooManyAuthorities
+		);
+	});
+}
+
+#[test]
+fn it_should_work_for_update_kitty_unsigned() {
+	use codec::Encode;
+	use frame_support::unsigned::ValidateUnsigned;
+	use sp_runtime::{
+		testing::{TestSignature, UintAuthorityId},
+		transaction_validity::{InvalidTransaction, TransactionSource},
+	};
+
+	new_test_ext().execute_with(|| {
+		let authority: u64 = 11;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(ALICE), commitment()));
+		assert_ok!(KittiesModule::add_authority(Origin::root(), authority));
+
+		let payload = KittyAssetPayload {
+			kitty_id,
+			asset: 7,
+			metadata_uri: Some(KittiesModule::asset_uri(7)),
+			public: UintAuthorityId(authority),
+		};
+		let signature = TestSignature(authority, payload.encode());
+		let call = Call::<Test>::update_kitty_unsigned {
+			payload: payload.clone(),
+			signature: signature.clone(),
+		};
+
+		//授权账户签名的交易有效，按kitty去重
+		let valid = KittiesModule::validate_unsigned(TransactionSource::External, &call).unwrap();
+		assert_eq!(valid.priority, <Test as Config>::UnsignedPriority::get());
+		assert_eq!(
+			valid.provides,
+			vec![("KittiesOffchainWorker", (b"update_kitty", kitty_id)).encode()]
+		);
+
+		assert_ok!(KittiesModule::update_kitty_unsigned(Origin::none(), payload, signature));
+		assert_eq!(Kitties::<Test>::get(kitty_id).unwrap().asset, 7);
+		assert_eq!(
+			KittiesModule::kitty_metadata(kitty_id).unwrap().metadata_uri.into_inner(),
+			b"kitty://asset/7".to_vec()
+		);
+
+		//数据已经上链，重复的交易无效
+		assert_eq!(
+			KittiesModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Stale.into()
+		);
+	});
+}
+
+#[test]
+fn update_kitty_unsigned_should_be_invalid_without_authority_signature() {
+	use codec::Encode;
+	use frame_support::unsigned::ValidateUnsigned;
+	use sp_runtime::{
+		testing::{TestSignature, UintAuthorityId},
+		transaction_validity::{InvalidTransaction, TransactionSource},
+	};
+
+	new_test_ext().execute_with(|| {
+		let authority: u64 = 11;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(ALICE), commitment()));
+
+		let payload = KittyAssetPayload {
+			kitty_id,
+			asset: 7,
+			metadata_uri: None,
+			public: UintAuthorityId(authority),
+		};
+
+		//签名与数据不匹配
+		let call = Call::<Test>::update_kitty_unsigned {
+			payload: payload.clone(),
+			signature: TestSignature(authority, vec![]),
+		};
+		assert_eq!(
+			KittiesModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadProof.into()
+		);
+
+		//签名正确，但不是授权账户
+		let call = Call::<Test>::update_kitty_unsigned {
+			payload: payload.clone(),
+			signature: TestSignature(authority, payload.encode()),
+		};
+		assert_eq!(
+			KittiesModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadSigner.into()
+		);
+
+		//不签名交易不能带签名来源
+		assert_noop!(
+			KittiesModule::update_kitty_unsigned(
+				Origin::signed(authority),
+				payload.clone(),
+				TestSignature(authority, payload.encode())
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn it_should_work_for_set_kitty_metadata() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		let reserved = <Test as Config>::Currency::reserved_balance(&alice);
+
+		//每个字节锁定1个单位的押金
+		assert_ok!(KittiesModule::set_kitty_metadata(
+			Origin::signed(alice),
+			kitty_id,
+			b"tom".to_vec(),
+			b"ipfs://tom".to_vec()
+		));
+		let metadata = KittiesModule::kitty_metadata(kitty_id).unwrap();
+		assert_eq!(metadata.name.into_inner(), b"tom".to_vec());
+		assert_eq!(metadata.metadata_uri.into_inner(), b"ipfs://tom".to_vec());
+		assert_eq!(metadata.deposit, 13);
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&alice), reserved + 13);
+		System::assert_has_event(TestEvent::KittiesModule(Event::MetadataSet(
+			alice,
+			kitty_id,
+			b"tom".to_vec(),
+			b"ipfs://tom".to_vec(),
+		)));
+
+		//元数据变短时退还多余的押金
+		assert_ok!(KittiesModule::set_kitty_metadata(
+			Origin::signed(alice),
+			kitty_id,
+			b"tom".to_vec(),
+			vec![]
+		));
+		assert_eq!(KittiesModule::kitty_metadata(kitty_id).unwrap().deposit, 3);
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&alice), reserved + 3);
+
+		//销毁时释放全部押金
+		pass_burn_cooldown(kitty_id);
+		assert_ok!(KittiesModule::burn(Origin::signed(alice), kitty_id));
+		assert!(KittiesModule::kitty_metadata(kitty_id).is_none());
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&alice), 0);
+	});
+}
+
+#[test]
+fn set_kitty_metadata_should_fail_when_invalid() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		assert_noop!(
+			KittiesModule::set_kitty_metadata(Origin::signed(bob), kitty_id, vec![], vec![]),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			KittiesModule::set_kitty_metadata(
+				Origin::signed(alice),
+				kitty_id,
+				vec![b'a'; 9],
+				vec![]
+			),
+			Error::<Test>::NameTooLong
+		);
+		assert_noop!(
+			KittiesModule::set_kitty_metadata(
+				Origin::signed(alice),
+				kitty_id,
+				vec![],
+				vec![b'a'; 33]
+			),
+			Error::<Test>::MetadataUriTooLong
+		);
+
+		//押金不足
+		let free = <Test as Config>::Currency::free_balance(&alice);
+		assert_ok!(<Test as Config>::Currency::reserve(&alice, free - 10));
+		assert_noop!(
+			KittiesModule::set_kitty_metadata(
+				Origin::signed(alice),
+				kitty_id,
+				vec![b'a'; 8],
+				vec![b'a'; 8]
+			),
+			Error::<Test>::NotEnoughBalance
+		);
+	});
+}
+
+#[test]
+fn metadata_deposit_should_move_with_kitty() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::set_kitty_metadata(
+			Origin::signed(alice),
+			kitty_id,
+			b"tom".to_vec(),
+			vec![]
+		));
+
+		assert_ok!(KittiesModule::transfer(Origin::signed(alice), kitty_id, bob));
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&alice), 0);
+		assert_eq!(
+			<Test as Config>::Currency::reserved_balance(&bob),
+			<Test as Config>::KittyPrice::get() + 3
+		);
+
+		//新的拥有者修改元数据时按差额补足押金
+		assert_ok!(KittiesModule::set_kitty_metadata(
+			Origin::signed(bob),
+			kitty_id,
+			b"jerry".to_vec(),
+			vec![]
+		));
+		assert_eq!(
+			<Test as Config>::Currency::reserved_balance(&bob),
+			<Test as Config>::KittyPrice::get() + 5
+		);
+	});
+}
+
+#[test]
+fn update_kitty_should_populate_metadata_uri_only_once() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let charlie: u64 = CHARLIE;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::add_authority(Origin::root(), charlie));
+
+		//链下工作机生成的图片地址写入元数据，不收取押金
+		let uri = KittiesModule::asset_uri(7);
+		assert_eq!(uri, b"kitty://asset/7".to_vec());
+		assert_ok!(KittiesModule::update_kitty(
+			Origin::signed(charlie),
+			kitty_id,
+			7,
+			Some(uri.clone())
+		));
+		let metadata = KittiesModule::kitty_metadata(kitty_id).unwrap();
+		assert_eq!(metadata.metadata_uri.into_inner(), uri.clone());
+		assert_eq!(metadata.deposit, 0);
+		System::assert_has_event(TestEvent::KittiesModule(Event::MetadataUriPopulated(
+			kitty_id, uri,
+		)));
+
+		//不覆盖已有的地址
+		assert_ok!(KittiesModule::set_kitty_metadata(
+			Origin::signed(alice),
+			kitty_id,
+			vec![],
+			b"ipfs://tom".to_vec()
+		));
+		assert_ok!(KittiesModule::update_kitty(
+			Origin::signed(charlie),
+			kitty_id,
+			8,
+			Some(KittiesModule::asset_uri(8))
+		));
+		assert_eq!(
+			KittiesModule::kitty_metadata(kitty_id).unwrap().metadata_uri.into_inner(),
+			b"ipfs://tom".to_vec()
+		);
+	});
+}
+
+#[test]
+fn it_should_work_for_auction() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let charlie: u64 = CHARLIE;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		<Test as Config>::Currency::make_free_balance_be(&charlie, 100);
+
+		//底价5，每次至少加价2，第5个区块结束
+		assert_ok!(KittiesModule::create_auction(Origin::signed(alice), kitty_id, 5, 2, 5));
+		assert_eq!(KittiesModule::auctions_ending_at(5).into_inner(), vec![kitty_id]);
+		System::assert_has_event(TestEvent::KittiesModule(Event::AuctionCreated(
+			alice, kitty_id, 5, 2, 5,
+		)));
+
+		//第一次出价不能低于底价
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(bob), kitty_id, 4),
+			Error::<Test>::BidTooLow
+		);
+		assert_ok!(KittiesModule::bid(Origin::signed(bob), kitty_id, 5));
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&bob), 5);
+
+		//之后的出价至少要加价2，被超过的出价自动退还
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(charlie), kitty_id, 6),
+			Error::<Test>::BidTooLow
+		);
+		assert_ok!(KittiesModule::bid(Origin::signed(charlie), kitty_id, 7));
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&bob), 0);
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&charlie), 7);
+		System::assert_has_event(TestEvent::KittiesModule(Event::BidRefunded(bob, kitty_id, 5)));
+		System::assert_has_event(TestEvent::KittiesModule(Event::BidPlaced(
+			charlie, kitty_id, 7,
+		)));
+
+		//拍卖期间不能转移
+		assert_noop!(
+			KittiesModule::transfer(Origin::signed(alice), kitty_id, bob),
+			Error::<Test>::KittyInAuction
+		);
+
+		//结束区块结算，出价付给卖家，kitty和押金转给买家
+		let alice_free = <Test as Config>::Currency::free_balance(&alice);
+		System::set_block_number(5);
+		KittiesModule::on_initialize(5);
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), Some(charlie));
+		assert_eq!(KittiesModule::auctions(kitty_id), None);
+		assert_eq!(
+			<Test as Config>::Currency::free_balance(&alice),
+			alice_free + 7 + <Test as Config>::KittyPrice::get()
+		);
+		assert_eq!(
+			<Test as Config>::Currency::reserved_balance(&charlie),
+			<Test as Config>::KittyPrice::get()
+		);
+		System::assert_has_event(TestEvent::KittiesModule(Event::AuctionSettled(
+			alice, charlie, kitty_id, 7,
+		)));
+
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(bob), kitty_id, 9),
+			Error::<Test>::AuctionNotFound
+		);
+	});
+}
+
+#[test]
+fn auction_should_fail_when_invalid() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		assert_noop!(
+			KittiesModule::create_auction(Origin::signed(bob), 0, 5, 2, 5),
+			Error::<Test>::NotOwner
+		);
+
+		//结束区块必须在将来，且不能超过最长拍卖时间
+		assert_noop!(
+			KittiesModule::create_auction(Origin::signed(alice), 0, 5, 2, 1),
+			Error::<Test>::InvalidAuctionEnd
+		);
+		assert_noop!(
+			KittiesModule::create_auction(Origin::signed(alice), 0, 5, 2, 12),
+			Error::<Test>::InvalidAuctionEnd
+		);
+
+		//最小加价幅度不能为0
+		assert_noop!(
+			KittiesModule::create_auction(Origin::signed(alice), 0, 5, 0, 5),
+			Error::<Test>::InvalidMinIncrement
+		);
+
+		assert_ok!(KittiesModule::create_auction(Origin::signed(alice), 0, 5, 2, 5));
+		assert_noop!(
+			KittiesModule::create_auction(Origin::signed(alice), 0, 5, 2, 6),
+			Error::<Test>::KittyInAuction
+		);
+		assert_noop!(
+			KittiesModule::set_price(Origin::signed(alice), 0, 5),
+			Error::<Test>::KittyInAuction
+		);
+		assert_noop!(KittiesModule::burn(Origin::signed(alice), 0), Error::<Test>::KittyInAuction);
+		assert_noop!(KittiesModule::bid(Origin::signed(alice), 0, 5), Error::<Test>::BidOwnAuction);
+
+		//拍卖期间元数据押金不能变化
+		assert_noop!(
+			KittiesModule::set_kitty_metadata(Origin::signed(alice), 0, b"tom".to_vec(), vec![]),
+			Error::<Test>::KittyInAuction
+		);
+
+		//同一个区块结束的拍卖数量有上限
+		assert_ok!(KittiesModule::create_auction(Origin::signed(alice), 1, 5, 2, 5));
+		assert_noop!(
+			KittiesModule::create_auction(Origin::signed(alice), 2, 5, 2, 5),
+			Error::<Test>::TooManyAuctions
+		);
+
+		//出价不能超过余额，余额还要够成交时锁定kitty押金
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(bob), 0, 26),
+			Error::<Test>::NotEnoughBalance
+		);
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(bob), 0, 16),
+			Error::<Test>::NotEnoughBalance
+		);
+		assert_ok!(KittiesModule::bid(Origin::signed(bob), 0, 15));
+
+		System::set_block_number(5);
+		assert_noop!(KittiesModule::bid(Origin::signed(bob), 0, 5), Error::<Test>::AuctionEnded);
+	});
+}
+
+#[test]
+fn auction_should_be_cancelled_or_unsold_without_bids() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		//没有出价时可以取消
+		assert_ok!(KittiesModule::create_auction(Origin::signed(alice), kitty_id, 5, 2, 5));
+		assert_noop!(
+			KittiesModule::cancel_auction(Origin::signed(bob), kitty_id),
+			Error::<Test>::NotOwner
+		);
+		assert_ok!(KittiesModule::cancel_auction(Origin::signed(alice), kitty_id));
+		assert_eq!(KittiesModule::auctions(kitty_id), None);
+		assert!(KittiesModule::auctions_ending_at(5).is_empty());
+		System::assert_has_event(TestEvent::KittiesModule(Event::AuctionCancelled(
+			alice, kitty_id,
+		)));
+
+		//没有出价的拍卖结束后kitty留在卖家名下
+		assert_ok!(KittiesModule::create_auction(Origin::signed(alice), kitty_id, 5, 2, 5));
+		KittiesModule::on_initialize(5);
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), Some(alice));
+		System::assert_has_event(TestEvent::KittiesModule(Event::AuctionUnsold(alice, kitty_id)));
+
+		//有出价后不能取消
+		assert_ok!(KittiesModule::create_auction(Origin::signed(alice), kitty_id, 5, 2, 6));
+		assert_ok!(KittiesModule::bid(Origin::signed(bob), kitty_id, 5));
+		assert_noop!(
+			KittiesModule::cancel_auction(Origin::signed(alice), kitty_id),
+			Error::<Test>::AuctionHasBids
+		);
+	});
+}
+
+#[test]
+fn auction_settlement_failure_should_refund_bid() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::create_auction(Origin::signed(alice), kitty_id, 5, 2, 5));
+
+		//bob出价后把剩下的余额用于创建kitty，不够锁定拍卖kitty的押金，结算回滚并退还出价
+		assert_ok!(KittiesModule::bid(Origin::signed(bob), kitty_id, 15));
+		assert_ok!(KittiesModule::create(Origin::signed(bob), commitment()));
+		KittiesModule::on_initialize(5);
+
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), Some(alice));
+		assert_eq!(KittiesModule::auctions(kitty_id), None);
+		assert_eq!(
+			<Test as Config>::Currency::reserved_balance(&bob),
+			<Test as Config>::KittyPrice::get()
+		);
+		assert_eq!(<Test as Config>::Currency::free_balance(&bob), 15);
+		System::assert_has_event(TestEvent::KittiesModule(Event::BidRefunded(bob, kitty_id, 15)));
+		System::assert_has_event(TestEvent::KittiesModule(Event::AuctionUnsold(alice, kitty_id)));
+	});
+}
+
+#[test]
+fn transfer_should_only_release_the_kitty_deposit() {
+	use codec::Encode;
+	use frame_support::{
+		storage::{migration::put_storage_value, unhashed},
+		traits::{OnRuntimeUpgrade, StorageVersion},
+		Blake2_128Concat, StorageHasher,
+	};
+
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_price: u64 = <Test as Config>::KittyPrice::get();
+
+		//bob 的kitty从 lesson2 迁移而来，创建时锁定了kitty价格
+		assert_ok!(<Test as Config>::Currency::reserve(&bob, kitty_price));
+		unhashed::put(&Kitties::<Test>::hashed_key_for(0), &migrations::v1::LegacyKitty([7u8; 16]));
+		KittyOwner::<Test>::insert(0, bob);
+		NextKittyId::<Test>::put(1);
+		put_storage_value(
+			b"KittiesModule",
+			migrations::v2::OLD_ALL_KITTIES,
+			&Blake2_128Concat::hash(&bob.encode()),
+			vec![0u32],
+		);
+		StorageVersion::new(0).put::<KittiesModule>();
+		migrations::Migration::<Test>::on_runtime_upgrade();
+		assert_eq!(KittiesModule::kitty_deposit(0), kitty_price);
+
+		//bob 在 alice 的拍卖中出价，出价被锁定
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::create_auction(Origin::signed(alice), 1, 5, 2, 5));
+		assert_ok!(KittiesModule::bid(Origin::signed(bob), 1, 5));
+
+		//转移迁移来的kitty不会释放bob的出价
+		assert_ok!(KittiesModule::transfer(Origin::signed(bob), 0, alice));
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&bob), 5);
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&alice), kitty_price * 2);
+		assert_eq!(KittiesModule::kitty_deposit(0), kitty_price);
+
+		//拍卖照常结算，bob 为买到的kitty锁定押金
+		KittiesModule::on_initialize(5);
+		assert_eq!(KittyOwner::<Test>::get(1), Some(bob));
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&bob), kitty_price);
+		assert_eq!(<Test as Config>::Currency::free_balance(&bob), 25 - 5 - kitty_price);
+	});
+}
+
+#[test]
+fn genesis_config_should_create_kitties() {
+	let male = [2u8; 16];
+	let female = [3u8; 16];
+	new_test_ext_with_kitties(vec![(ALICE, male), (ALICE, female), (BOB, male)]).execute_with(|| {
+		let kitty_price: u64 = <Test as Config>::KittyPrice::get();
+
+		assert_eq!(NextKittyId::<Test>::get(), 3);
+		assert_eq!(KittiesModule::total_supply(), 3);
+		assert_eq!(KittyOwner::<Test>::get(0), Some(ALICE));
+		assert_eq!(KittyOwner::<Test>::get(2), Some(BOB));
+		assert!(KittiesModule::owns(&ALICE, 0) && KittiesModule::owns(&ALICE, 1));
+		assert_eq!(KittiesModule::owned_count(&ALICE), 2);
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&ALICE), kitty_price * 2);
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&BOB), kitty_price);
+
+		//创世kitty的dna已经揭示，资源编号由dna决定，可以直接繁殖
+		let kitty = KittiesModule::kitties(1).unwrap();
+		assert_eq!(kitty.dna, female);
+		assert_eq!(kitty.asset, KittiesModule::asset_from_dna(&female));
+		assert!(KittiesModule::is_dna_revealed(1));
+		assert_ok!(KittiesModule::breed(Origin::signed(ALICE), 0, 1, 0, commitment()));
+		assert_eq!(KittyOwner::<Test>::get(3), Some(ALICE));
+	});
+}
+
+#[test]
+#[should_panic(expected = "genesis kitty owner cannot reserve the kitty price")]
+fn genesis_config_should_panic_when_owner_cannot_reserve() {
+	new_test_ext_with_kitties(vec![(CHARLIE, [0u8; 16])]);
+}
+
+#[test]
+fn it_should_work_for_lend_kitty() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let lent_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		let bob_kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(bob), commitment()));
+		set_gender(lent_id, Gender::Female);
+		set_gender(bob_kitty_id, Gender::Male);
+
+		assert_ok!(KittiesModule::set_price(Origin::signed(alice), lent_id, 5));
+		assert_ok!(KittiesModule::lend(Origin::signed(alice), lent_id, bob, 5));
+		assert_eq!(KittiesModule::kitty_holder(lent_id), Some((bob, 5)));
+		assert_eq!(KittiesModule::kitties_for_sale(lent_id), None);
+		System::assert_has_event(TestEvent::KittiesModule(Event::KittyLent(
+			alice, bob, lent_id, 5,
+		)));
+
+		//出借期间拥有者不变，但不能转移、出售或再次出借
+		assert_eq!(KittyOwner::<Test>::get(lent_id), Some(alice));
+		assert_noop!(
+			KittiesModule::transfer(Origin::signed(alice), lent_id, bob),
+			Error::<Test>::KittyLent
+		);
+		assert_noop!(
+			KittiesModule::set_price(Origin::signed(alice), lent_id, 5),
+			Error::<Test>::KittyLent
+		);
+		assert_noop!(
+			KittiesModule::lend(Origin::signed(alice), lent_id, CHARLIE, 5),
+			Error::<Test>::KittyLent
+		);
+
+		//持有者可以直接用它繁殖，不需要支付配种费用，新kitty属于持有者
+		let new_kitty_id = NextKittyId::<Test>::get();
+		let alice_free = <Test as Config>::Currency::free_balance(&alice);
+		assert_ok!(KittiesModule::breed(
+			Origin::signed(bob),
+			bob_kitty_id,
+			lent_id,
+			0,
+			commitment()
+		));
+		assert_eq!(KittyOwner::<Test>::get(new_kitty_id), Some(bob));
+		assert_eq!(<Test as Config>::Currency::free_balance(&alice), alice_free);
+
+		//到期后在 on_initialize 中自动归还
+		KittiesModule::on_initialize(5);
+		assert_eq!(KittiesModule::kitty_holder(lent_id), None);
+		assert_eq!(KittiesModule::loans_ending_at(5).len(), 0);
+		System::assert_has_event(TestEvent::KittiesModule(Event::KittyReturned(
+			alice, bob, lent_id,
+		)));
+		assert_ok!(KittiesModule::transfer(Origin::signed(alice), lent_id, bob));
+	});
+}
+
+#[test]
+fn lend_kitty_should_fail_when_invalid() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		assert_noop!(
+			KittiesModule::lend(Origin::signed(bob), kitty_id, bob, 5),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			KittiesModule::lend(Origin::signed(alice), kitty_id, alice, 5),
+			Error::<Test>::LendToSelf
+		);
+
+		//归还区块必须在将来，且不超过 MaxLoanDuration
+		assert_noop!(
+			KittiesModule::lend(Origin::signed(alice), kitty_id, bob, 1),
+			Error::<Test>::InvalidLoanEnd
+		);
+		assert_noop!(
+			KittiesModule::lend(Origin::signed(alice), kitty_id, bob, 12),
+			Error::<Test>::InvalidLoanEnd
+		);
+
+		//同一个区块最多到期 MaxLoansPerBlock 笔出借
+		assert_ok!(KittiesModule::lend(Origin::signed(alice), kitty_id, bob, 5));
+		assert_ok!(KittiesModule::lend(Origin::signed(alice), kitty_id + 1, bob, 5));
+		assert_noop!(
+			KittiesModule::lend(Origin::signed(alice), kitty_id + 2, bob, 5),
+			Error::<Test>::TooManyLoans
+		);
+
+		//出借期间拥有者不能为别人提供配种服务
+		assert_noop!(
+			KittiesModule::offer_siring(Origin::signed(alice), kitty_id, 3),
+			Error::<Test>::KittyLent
+		);
+	});
+}
+
+#[test]
+fn kitty_traits_should_work() {
+	new_test_ext().execute_with(|| {
+		let alice: u64 = ALICE;
+		let bob: u64 = BOB;
+		let kitty_id = NextKittyId::<Test>::get();
+		assert_ok!(KittiesModule::create(Origin::signed(alice), commitment()));
+
+		assert_eq!(<KittiesModule as KittyInspect<u64>>::owner(&kitty_id), Some(alice));
+		assert_eq!(<KittiesModule as KittyInspect<u64>>::owner(&(kitty_id + 1)), None);
+
+		//dna揭示之前查询不到
+		assert_eq!(<KittiesModule as KittyInspect<u64>>::dna(&kitty_id), None);
+		reveal_dna(kitty_id);
+		assert_eq!(
+			<KittiesModule as KittyInspect<u64>>::dna(&kitty_id),
+			Some(KittiesModule::kitties(kitty_id).unwrap().dna)
+		);
+
+		//出借中的kitty不能转移
+		assert_ok!(KittiesModule::lend(Origin::signed(alice), kitty_id, bob, 5));
+		assert!(!<KittiesModule as KittyInspect<u64>>::can_transfer(&kitty_id));
+		assert_noop!(
+			<KittiesModule as KittyTransfer<u64>>::transfer(&kitty_id, &bob),
+			Error::<Test>::KittyLent
+		);
+
+		KittiesModule::on_initialize(5);
+		assert!(<KittiesModule as KittyInspect<u64>>::can_transfer(&kitty_id));
+		assert_ok!(<KittiesModule as KittyTransfer<u64>>::transfer(&kitty_id, &bob));
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), Some(bob));
+		System::assert_last_event(TestEvent::KittiesModule(Event::KittyTransferred(
+			alice, bob, kitty_id,
+		)));
+	});
+}
@@ -0,0 +1,36 @@
+//供其它pallet使用的kitty接口，类似 frame_support::traits::tokens::nonfungibles
+//
+//其它pallet只需要配置 `type Kitties: KittyTransfer<Self::AccountId>`，不需要直接读写kitties的存储
+
+use frame_support::dispatch::DispatchResult;
+
+//查询kitty
+pub trait KittyInspect<AccountId> {
+	//kitty的id类型
+	type KittyId;
+
+	//kitty的拥有者，kitty不存在时返回None
+	fn owner(kitty_id: &Self::KittyId) -> Option<AccountId>;
+
+	//kitty的dna，kitty不存在或dna还没有揭示时返回None
+	fn dna(kitty_id: &Self::KittyId) -> Option<[u8; 16]>;
+
+	//kitty现在能否转移，拍卖中或出借中的kitty不能转移
+	fn can_transfer(kitty_id: &Self::KittyId) -> bool;
+}
+
+//转移kitty
+pub trait KittyTransfer<AccountId>: KittyInspect<AccountId> {
+	//把kitty从拥有者转给 destination，押金规则和 transfer 调用相同，失败时不改变任何状态
+	fn transfer(kitty_id: &Self::KittyId, destination: &AccountId) -> DispatchResult;
+}
+
+//kitty易主或销毁之后通知其它pallet，to 为None表示kitty被销毁。
+//在转移或销毁的调用中执行，实现需要保持轻量，它的读写计入这些调用的权重
+pub trait OnKittyTransfer<AccountId, KittyId> {
+	fn on_kitty_transfer(kitty_id: &KittyId, from: &AccountId, to: Option<&AccountId>);
+}
+
+impl<AccountId, KittyId> OnKittyTransfer<AccountId, KittyId> for () {
+	fn on_kitty_transfer(_kitty_id: &KittyId, _from: &AccountId, _to: Option<&AccountId>) {}
+}
@@ -0,0 +1,536 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Weights for pallet_kitties
+//!
+//! These weights are hand estimates, not benchmark output. The storage reads and writes follow
+//! the worst cases set up in `benchmarking.rs`, but the base and per-item times have not been
+//! measured. Regenerate this file with `./scripts/benchmark.sh pallet_kitties`, run on
+//! reference hardware, before relying on them.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_kitties.
+pub trait WeightInfo {
+	fn create() -> Weight;
+	fn breed() -> Weight;
+	fn transfer() -> Weight;
+	fn approve() -> Weight;
+	fn set_approval_for_all() -> Weight;
+	fn transfer_from() -> Weight;
+	fn burn() -> Weight;
+	fn offer_siring() -> Weight;
+	fn cancel_siring() -> Weight;
+	fn set_price() -> Weight;
+	fn cancel_sale() -> Weight;
+	fn buy() -> Weight;
+	fn create_auction() -> Weight;
+	fn bid() -> Weight;
+	fn cancel_auction() -> Weight;
+	fn lend() -> Weight;
+	fn set_kitty_metadata(b: u32, ) -> Weight;
+	fn update_kitty(u: u32, ) -> Weight;
+	fn add_authority() -> Weight;
+	fn remove_authority() -> Weight;
+	fn reveal_dna() -> Weight;
+}
+
+/// Hand-estimated weights for pallet_kitties, see the module docs.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: Kitties KittyDeposit (r:0 w:1)
+	// Storage: Kitties DnaCommitments (r:0 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Kitties NextKittyId (r:1 w:1)
+	// Storage: Kitties OwnedKittiesCount (r:1 w:1)
+	// Storage: Kitties Kitties (r:0 w:1)
+	// Storage: Kitties KittyOwner (r:0 w:1)
+	// Storage: Kitties PendingDnaCount (r:1 w:1)
+	// Storage: Kitties PendingDna (r:0 w:1)
+	// Storage: Kitties OwnedKitties (r:0 w:1)
+	fn create() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(10 as Weight))
+	}
+	// Storage: Kitties KittyDeposit (r:0 w:1)
+	// Storage: Kitties DnaCommitments (r:0 w:1)
+	// Storage: System Account (r:3 w:3)
+	// Storage: Kitties Kitties (r:2 w:1)
+	// Storage: Kitties DnaRevealedAt (r:2 w:0)
+	// Storage: Kitties PendingDnaCount (r:1 w:1)
+	// Storage: Kitties PendingDna (r:0 w:1)
+	// Storage: Kitties KittyOwner (r:2 w:1)
+	// Storage: Kitties KittyHolder (r:2 w:0)
+	// Storage: Kitties SiringOffers (r:2 w:0)
+	// Storage: Kitties KittyReadyAt (r:2 w:2)
+	// Storage: Kitties ChildrenCount (r:2 w:2)
+	// Storage: Kitties NextKittyId (r:1 w:1)
+	// Storage: Kitties OwnedKittiesCount (r:1 w:1)
+	// Storage: Kitties OwnedKitties (r:0 w:1)
+	fn breed() -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(20 as Weight))
+			.saturating_add(T::DbWeight::get().writes(16 as Weight))
+	}
+	// Storage: Kitties KittyDeposit (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Kitties Kitties (r:1 w:0)
+	// Storage: Kitties KittyOwner (r:1 w:1)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	// Storage: Kitties KittyMetadataOf (r:1 w:0)
+	// Storage: Kitties OwnedKitties (r:1 w:2)
+	// Storage: Kitties OwnedKittiesCount (r:2 w:2)
+	// Storage: Kitties KittiesForSale (r:0 w:1)
+	// Storage: Kitties SiringOffers (r:0 w:1)
+	// Storage: Kitties KittyApprovals (r:0 w:1)
+	// Storage: KittySwap Swaps (r:1 w:1)
+	fn transfer() -> Weight {
+		(60_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(12 as Weight))
+			.saturating_add(T::DbWeight::get().writes(12 as Weight))
+	}
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties OperatorApprovals (r:1 w:0)
+	// Storage: Kitties KittyApprovals (r:0 w:1)
+	fn approve() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties OperatorApprovals (r:0 w:1)
+	fn set_approval_for_all() -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties KittyDeposit (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Kitties Kitties (r:1 w:0)
+	// Storage: Kitties KittyOwner (r:1 w:1)
+	// Storage: Kitties KittyApprovals (r:1 w:1)
+	// Storage: Kitties OperatorApprovals (r:1 w:0)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	// Storage: Kitties KittyMetadataOf (r:1 w:0)
+	// Storage: Kitties OwnedKitties (r:1 w:2)
+	// Storage: Kitties OwnedKittiesCount (r:2 w:2)
+	// Storage: Kitties KittiesForSale (r:0 w:1)
+	// Storage: Kitties SiringOffers (r:0 w:1)
+	// Storage: KittySwap Swaps (r:1 w:1)
+	fn transfer_from() -> Weight {
+		(65_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(14 as Weight))
+			.saturating_add(T::DbWeight::get().writes(12 as Weight))
+	}
+	// Storage: Kitties KittyDeposit (r:1 w:1)
+	// Storage: Kitties Kitties (r:1 w:1)
+	// Storage: Kitties KittyOwner (r:1 w:1)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	// Storage: Kitties OwnedKitties (r:1 w:1)
+	// Storage: Kitties OwnedKittiesCount (r:1 w:1)
+	// Storage: Kitties KittyMetadataOf (r:1 w:1)
+	// Storage: Kitties BurnedKitties (r:1 w:1)
+	// Storage: Kitties DnaRevealedAt (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances TotalIssuance (r:1 w:1)
+	// Storage: Kitties KittiesForSale (r:0 w:1)
+	// Storage: Kitties SiringOffers (r:0 w:1)
+	// Storage: Kitties KittyApprovals (r:0 w:1)
+	// Storage: Kitties KittyReadyAt (r:0 w:1)
+	// Storage: Kitties ChildrenCount (r:0 w:1)
+	// Storage: KittySwap Swaps (r:1 w:1)
+	fn burn() -> Weight {
+		(55_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(13 as Weight))
+			.saturating_add(T::DbWeight::get().writes(16 as Weight))
+	}
+	// Storage: Kitties Kitties (r:1 w:0)
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	// Storage: Kitties SiringOffers (r:0 w:1)
+	fn offer_siring() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties SiringOffers (r:1 w:1)
+	fn cancel_siring() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties Kitties (r:1 w:0)
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	// Storage: Kitties KittiesForSale (r:0 w:1)
+	fn set_price() -> Weight {
+		(22_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties KittiesForSale (r:1 w:1)
+	fn cancel_sale() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties KittyDeposit (r:1 w:1)
+	// Storage: Kitties KittiesForSale (r:1 w:1)
+	// Storage: Kitties KittyOwner (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	// Storage: Kitties KittyMetadataOf (r:1 w:0)
+	// Storage: Kitties OwnedKitties (r:1 w:2)
+	// Storage: Kitties OwnedKittiesCount (r:2 w:2)
+	// Storage: Kitties SiringOffers (r:0 w:1)
+	// Storage: Kitties KittyApprovals (r:0 w:1)
+	// Storage: KittySwap Swaps (r:1 w:1)
+	fn buy() -> Weight {
+		(75_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(12 as Weight))
+			.saturating_add(T::DbWeight::get().writes(12 as Weight))
+	}
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties Auctions (r:1 w:1)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	// Storage: Kitties AuctionsEndingAt (r:1 w:1)
+	// Storage: Kitties KittiesForSale (r:0 w:1)
+	fn create_auction() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Kitties Auctions (r:1 w:1)
+	// Storage: Kitties KittyMetadataOf (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	fn bid() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Kitties Auctions (r:1 w:1)
+	// Storage: Kitties AuctionsEndingAt (r:1 w:1)
+	fn cancel_auction() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:1)
+	// Storage: Kitties LoansEndingAt (r:1 w:1)
+	// Storage: Kitties KittiesForSale (r:0 w:1)
+	// Storage: Kitties SiringOffers (r:0 w:1)
+	fn lend() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyMetadataOf (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[0, 160]`.
+	fn set_kitty_metadata(b: u32, ) -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Kitties Authorities (r:1 w:0)
+	// Storage: Kitties Kitties (r:1 w:1)
+	// Storage: Kitties KittyMetadataOf (r:1 w:1)
+	/// The range of component `u` is `[0, 128]`.
+	fn update_kitty(u: u32, ) -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(u as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Kitties Authorities (r:1 w:1)
+	fn add_authority() -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties Authorities (r:1 w:1)
+	fn remove_authority() -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties DnaCommitments (r:1 w:1)
+	// Storage: Kitties DrawnDna (r:1 w:1)
+	// Storage: Kitties Kitties (r:1 w:1)
+	// Storage: Kitties DnaRevealedAt (r:0 w:1)
+	fn reveal_dna() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: Kitties KittyDeposit (r:0 w:1)
+	// Storage: Kitties DnaCommitments (r:0 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Kitties NextKittyId (r:1 w:1)
+	// Storage: Kitties OwnedKittiesCount (r:1 w:1)
+	// Storage: Kitties Kitties (r:0 w:1)
+	// Storage: Kitties KittyOwner (r:0 w:1)
+	// Storage: Kitties PendingDnaCount (r:1 w:1)
+	// Storage: Kitties PendingDna (r:0 w:1)
+	// Storage: Kitties OwnedKitties (r:0 w:1)
+	fn create() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(10 as Weight))
+	}
+	// Storage: Kitties KittyDeposit (r:0 w:1)
+	// Storage: Kitties DnaCommitments (r:0 w:1)
+	// Storage: System Account (r:3 w:3)
+	// Storage: Kitties Kitties (r:2 w:1)
+	// Storage: Kitties DnaRevealedAt (r:2 w:0)
+	// Storage: Kitties PendingDnaCount (r:1 w:1)
+	// Storage: Kitties PendingDna (r:0 w:1)
+	// Storage: Kitties KittyOwner (r:2 w:1)
+	// Storage: Kitties KittyHolder (r:2 w:0)
+	// Storage: Kitties SiringOffers (r:2 w:0)
+	// Storage: Kitties KittyReadyAt (r:2 w:2)
+	// Storage: Kitties ChildrenCount (r:2 w:2)
+	// Storage: Kitties NextKittyId (r:1 w:1)
+	// Storage: Kitties OwnedKittiesCount (r:1 w:1)
+	// Storage: Kitties OwnedKitties (r:0 w:1)
+	fn breed() -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(20 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(16 as Weight))
+	}
+	// Storage: Kitties KittyDeposit (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Kitties Kitties (r:1 w:0)
+	// Storage: Kitties KittyOwner (r:1 w:1)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	// Storage: Kitties KittyMetadataOf (r:1 w:0)
+	// Storage: Kitties OwnedKitties (r:1 w:2)
+	// Storage: Kitties OwnedKittiesCount (r:2 w:2)
+	// Storage: Kitties KittiesForSale (r:0 w:1)
+	// Storage: Kitties SiringOffers (r:0 w:1)
+	// Storage: Kitties KittyApprovals (r:0 w:1)
+	// Storage: KittySwap Swaps (r:1 w:1)
+	fn transfer() -> Weight {
+		(60_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(12 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(12 as Weight))
+	}
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties OperatorApprovals (r:1 w:0)
+	// Storage: Kitties KittyApprovals (r:0 w:1)
+	fn approve() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties OperatorApprovals (r:0 w:1)
+	fn set_approval_for_all() -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties KittyDeposit (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Kitties Kitties (r:1 w:0)
+	// Storage: Kitties KittyOwner (r:1 w:1)
+	// Storage: Kitties KittyApprovals (r:1 w:1)
+	// Storage: Kitties OperatorApprovals (r:1 w:0)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	// Storage: Kitties KittyMetadataOf (r:1 w:0)
+	// Storage: Kitties OwnedKitties (r:1 w:2)
+	// Storage: Kitties OwnedKittiesCount (r:2 w:2)
+	// Storage: Kitties KittiesForSale (r:0 w:1)
+	// Storage: Kitties SiringOffers (r:0 w:1)
+	// Storage: KittySwap Swaps (r:1 w:1)
+	fn transfer_from() -> Weight {
+		(65_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(14 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(12 as Weight))
+	}
+	// Storage: Kitties KittyDeposit (r:1 w:1)
+	// Storage: Kitties Kitties (r:1 w:1)
+	// Storage: Kitties KittyOwner (r:1 w:1)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	// Storage: Kitties OwnedKitties (r:1 w:1)
+	// Storage: Kitties OwnedKittiesCount (r:1 w:1)
+	// Storage: Kitties KittyMetadataOf (r:1 w:1)
+	// Storage: Kitties BurnedKitties (r:1 w:1)
+	// Storage: Kitties DnaRevealedAt (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances TotalIssuance (r:1 w:1)
+	// Storage: Kitties KittiesForSale (r:0 w:1)
+	// Storage: Kitties SiringOffers (r:0 w:1)
+	// Storage: Kitties KittyApprovals (r:0 w:1)
+	// Storage: Kitties KittyReadyAt (r:0 w:1)
+	// Storage: Kitties ChildrenCount (r:0 w:1)
+	// Storage: KittySwap Swaps (r:1 w:1)
+	fn burn() -> Weight {
+		(55_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(13 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(16 as Weight))
+	}
+	// Storage: Kitties Kitties (r:1 w:0)
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	// Storage: Kitties SiringOffers (r:0 w:1)
+	fn offer_siring() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties SiringOffers (r:1 w:1)
+	fn cancel_siring() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties Kitties (r:1 w:0)
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	// Storage: Kitties KittiesForSale (r:0 w:1)
+	fn set_price() -> Weight {
+		(22_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties KittiesForSale (r:1 w:1)
+	fn cancel_sale() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties KittyDeposit (r:1 w:1)
+	// Storage: Kitties KittiesForSale (r:1 w:1)
+	// Storage: Kitties KittyOwner (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	// Storage: Kitties KittyMetadataOf (r:1 w:0)
+	// Storage: Kitties OwnedKitties (r:1 w:2)
+	// Storage: Kitties OwnedKittiesCount (r:2 w:2)
+	// Storage: Kitties SiringOffers (r:0 w:1)
+	// Storage: Kitties KittyApprovals (r:0 w:1)
+	// Storage: KittySwap Swaps (r:1 w:1)
+	fn buy() -> Weight {
+		(75_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(12 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(12 as Weight))
+	}
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties Auctions (r:1 w:1)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	// Storage: Kitties AuctionsEndingAt (r:1 w:1)
+	// Storage: Kitties KittiesForSale (r:0 w:1)
+	fn create_auction() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Kitties Auctions (r:1 w:1)
+	// Storage: Kitties KittyMetadataOf (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	fn bid() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Kitties Auctions (r:1 w:1)
+	// Storage: Kitties AuctionsEndingAt (r:1 w:1)
+	fn cancel_auction() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:1)
+	// Storage: Kitties LoansEndingAt (r:1 w:1)
+	// Storage: Kitties KittiesForSale (r:0 w:1)
+	// Storage: Kitties SiringOffers (r:0 w:1)
+	fn lend() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Kitties KittyOwner (r:1 w:0)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyMetadataOf (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[0, 160]`.
+	fn set_kitty_metadata(b: u32, ) -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Kitties Authorities (r:1 w:0)
+	// Storage: Kitties Kitties (r:1 w:1)
+	// Storage: Kitties KittyMetadataOf (r:1 w:1)
+	/// The range of component `u` is `[0, 128]`.
+	fn update_kitty(u: u32, ) -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(u as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Kitties Authorities (r:1 w:1)
+	fn add_authority() -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties Authorities (r:1 w:1)
+	fn remove_authority() -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Kitties DnaCommitments (r:1 w:1)
+	// Storage: Kitties DrawnDna (r:1 w:1)
+	// Storage: Kitties Kitties (r:1 w:1)
+	// Storage: Kitties DnaRevealedAt (r:0 w:1)
+	fn reveal_dna() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+}
@@ -0,0 +1,54 @@
+//! Benchmarking setup for pallet-kitty-swap
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as KittySwap;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_system::RawOrigin;
+use pallet_kitties::KittyInspect;
+
+const SEED: u32 = 0;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+benchmarks! {
+	propose_swap {
+		let caller: T::AccountId = whitelisted_caller();
+		let counterparty: T::AccountId = account("counterparty", 0, SEED);
+		let offered = T::BenchmarkHelper::mint(&caller);
+		let wanted = T::BenchmarkHelper::mint(&counterparty);
+	}: _(RawOrigin::Signed(caller.clone()), offered, wanted)
+	verify {
+		assert_last_event::<T>(Event::SwapProposed(caller, offered, wanted).into());
+	}
+
+	cancel_swap {
+		let caller: T::AccountId = whitelisted_caller();
+		let counterparty: T::AccountId = account("counterparty", 0, SEED);
+		let offered = T::BenchmarkHelper::mint(&caller);
+		let wanted = T::BenchmarkHelper::mint(&counterparty);
+		Swaps::<T>::insert(offered, (caller.clone(), wanted));
+	}: _(RawOrigin::Signed(caller.clone()), offered)
+	verify {
+		assert_last_event::<T>(Event::SwapCancelled(caller, offered).into());
+	}
+
+	//最坏情况下想要的kitty也提出了交换，转移时要删除它提出的交换
+	accept_swap {
+		let proposer: T::AccountId = account("proposer", 0, SEED);
+		let caller: T::AccountId = whitelisted_caller();
+		let offered = T::BenchmarkHelper::mint(&proposer);
+		let wanted = T::BenchmarkHelper::mint(&caller);
+		Swaps::<T>::insert(offered, (proposer.clone(), wanted));
+		Swaps::<T>::insert(wanted, (caller.clone(), offered));
+	}: _(RawOrigin::Signed(caller.clone()), offered)
+	verify {
+		assert_eq!(T::Kitties::owner(&offered), Some(caller.clone()));
+		assert_last_event::<T>(Event::SwapAccepted(proposer, caller, offered, wanted).into());
+	}
+
+	impl_benchmark_test_suite!(KittySwap, crate::mock::new_test_ext(), crate::mock::Test);
+}
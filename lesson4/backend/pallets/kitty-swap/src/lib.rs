@@ -0,0 +1,158 @@
+#![cfg_attr(not(feature = "std"), no_std)] //编译标签
+
+//kitty交换：一方提出用自己的kitty交换对方的kitty，对方接受后两只kitty同时易主
+//kitty的查询和转移都通过 pallet_kitties::KittyTransfer 接口完成，不直接读写kitties的存储
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+
+//基准测试需要真实的kitty，由runtime提供铸造kitty的方法
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<AccountId, KittyId> {
+	//给 owner 铸造一只可以转移的kitty，owner 有足够的余额锁定押金
+	fn mint(owner: &AccountId) -> KittyId;
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{pallet_prelude::*, transactional};
+	use frame_system::pallet_prelude::*;
+	use pallet_kitties::{KittyTransfer, OnKittyTransfer};
+
+	use super::WeightInfo;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		//kitty的id类型，和 Kitties 的 KittyId 相同
+		type KittyId: Parameter + MaxEncodedLen + Copy;
+
+		//提供kitty的pallet，runtime中配置为 pallet_kitties::Pallet
+		type Kitties: KittyTransfer<Self::AccountId, KittyId = Self::KittyId>;
+
+		type WeightInfo: WeightInfo;
+
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: super::BenchmarkHelper<Self::AccountId, Self::KittyId>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	//定义存储，提出的交换：提出者的kitty -> (提出者, 想要的kitty)
+	#[pallet::storage]
+	#[pallet::getter(fn swaps)]
+	pub type Swaps<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyId, (T::AccountId, T::KittyId)>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		SwapProposed(T::AccountId, T::KittyId, T::KittyId),
+		SwapCancelled(T::AccountId, T::KittyId),
+		SwapAccepted(T::AccountId, T::AccountId, T::KittyId, T::KittyId),
+		//提出者的kitty已经易主或销毁，交换被删除
+		SwapInvalidated(T::AccountId, T::KittyId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		InvalidKittyId,
+		NotOwner,
+		SwapWithSelf,
+		NotTransferable,
+		SwapAlreadyExist,
+		SwapNotFound,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		//用自己的 offered 交换别人的 wanted
+		#[pallet::weight(T::WeightInfo::propose_swap())]
+		pub fn propose_swap(
+			origin: OriginFor<T>,
+			offered: T::KittyId,
+			wanted: T::KittyId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(T::Kitties::owner(&offered) == Some(who.clone()), Error::<T>::NotOwner);
+			let counterparty = T::Kitties::owner(&wanted).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(counterparty != who, Error::<T>::SwapWithSelf);
+			ensure!(T::Kitties::can_transfer(&offered), Error::<T>::NotTransferable);
+			ensure!(!Swaps::<T>::contains_key(&offered), Error::<T>::SwapAlreadyExist);
+
+			Swaps::<T>::insert(&offered, (who.clone(), wanted));
+
+			Self::deposit_event(Event::SwapProposed(who, offered, wanted));
+
+			Ok(())
+		}
+
+		//取消自己提出的交换
+		#[pallet::weight(T::WeightInfo::cancel_swap())]
+		pub fn cancel_swap(origin: OriginFor<T>, offered: T::KittyId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let (proposer, _) = Self::swaps(&offered).ok_or(Error::<T>::SwapNotFound)?;
+			ensure!(proposer == who, Error::<T>::NotOwner);
+
+			Swaps::<T>::remove(&offered);
+
+			Self::deposit_event(Event::SwapCancelled(who, offered));
+
+			Ok(())
+		}
+
+		//想要的kitty的拥有者接受交换，两只kitty同时易主，任何一只转移失败都会回滚
+		#[pallet::weight(T::WeightInfo::accept_swap())]
+		#[transactional]
+		pub fn accept_swap(origin: OriginFor<T>, offered: T::KittyId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let (proposer, wanted) = Self::swaps(&offered).ok_or(Error::<T>::SwapNotFound)?;
+			ensure!(T::Kitties::owner(&wanted) == Some(who.clone()), Error::<T>::NotOwner);
+
+			Swaps::<T>::remove(&offered);
+
+			//提出交换之后 offered 可能已经转给了别人，只删除失效的交换，不能返回错误让删除回滚
+			if T::Kitties::owner(&offered) != Some(proposer.clone()) {
+				Self::deposit_event(Event::SwapInvalidated(proposer, offered));
+				return Ok(())
+			}
+
+			T::Kitties::transfer(&offered, &who)?;
+			T::Kitties::transfer(&wanted, &proposer)?;
+
+			Self::deposit_event(Event::SwapAccepted(proposer, who, offered, wanted));
+
+			Ok(())
+		}
+	}
+
+	//kitty易主或销毁后，以它提出的交换不再有效
+	impl<T: Config> OnKittyTransfer<T::AccountId, T::KittyId> for Pallet<T> {
+		fn on_kitty_transfer(
+			kitty_id: &T::KittyId,
+			_from: &T::AccountId,
+			_to: Option<&T::AccountId>,
+		) {
+			if let Some((proposer, _)) = Swaps::<T>::take(kitty_id) {
+				Self::deposit_event(Event::SwapInvalidated(proposer, *kitty_id));
+			}
+		}
+	}
+}
@@ -0,0 +1,139 @@
+use crate as pallet_kitty_swap;
+use codec::Encode;
+use frame_support::{
+	dispatch::DispatchResult,
+	storage::unhashed,
+	traits::{ConstU16, ConstU64},
+};
+use pallet_kitties::{KittyInspect, KittyTransfer, OnKittyTransfer};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	DispatchError,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		KittySwap: pallet_kitty_swap::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+//模拟的kitty存在测试环境的存储中，和交易一起回滚
+const OWNER_PREFIX: &[u8] = b"mock_kitties::owner";
+const LOCKED_PREFIX: &[u8] = b"mock_kitties::locked";
+#[cfg(feature = "runtime-benchmarks")]
+const NEXT_ID_KEY: &[u8] = b"mock_kitties::next_id";
+
+//用存储中的拥有者表代替 pallet_kitties
+pub struct MockKitties;
+
+impl MockKitties {
+	pub fn mint(kitty_id: u32, owner: u64) {
+		unhashed::put(&(OWNER_PREFIX, kitty_id).encode(), &owner);
+	}
+
+	//销毁kitty，和 pallet_kitties 一样通知交换pallet
+	pub fn burn(kitty_id: u32) {
+		if let Some(owner) = Self::owner(&kitty_id) {
+			unhashed::kill(&(OWNER_PREFIX, kitty_id).encode());
+			KittySwap::on_kitty_transfer(&kitty_id, &owner, None);
+		}
+	}
+
+	//不能转移的kitty，模拟拍卖中或出借中的kitty
+	pub fn lock(kitty_id: u32) {
+		unhashed::put(&(LOCKED_PREFIX, kitty_id).encode(), &true);
+	}
+}
+
+impl KittyInspect<u64> for MockKitties {
+	type KittyId = u32;
+
+	fn owner(kitty_id: &u32) -> Option<u64> {
+		unhashed::get(&(OWNER_PREFIX, kitty_id).encode())
+	}
+
+	fn dna(kitty_id: &u32) -> Option<[u8; 16]> {
+		Self::owner(kitty_id).map(|_| [*kitty_id as u8; 16])
+	}
+
+	fn can_transfer(kitty_id: &u32) -> bool {
+		Self::owner(kitty_id).is_some() && !unhashed::exists(&(LOCKED_PREFIX, kitty_id).encode())
+	}
+}
+
+impl KittyTransfer<u64> for MockKitties {
+	fn transfer(kitty_id: &u32, destination: &u64) -> DispatchResult {
+		if !Self::can_transfer(kitty_id) {
+			return Err(DispatchError::Other("kitty cannot be transferred"))
+		}
+		let owner = Self::owner(kitty_id).expect("transferable kitty has an owner");
+		Self::mint(*kitty_id, *destination);
+		KittySwap::on_kitty_transfer(kitty_id, &owner, Some(destination));
+		Ok(())
+	}
+}
+
+impl pallet_kitty_swap::Config for Test {
+	type Event = Event;
+	type KittyId = u32;
+	type Kitties = MockKitties;
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockKitties;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::BenchmarkHelper<u64, u32> for MockKitties {
+	fn mint(owner: &u64) -> u32 {
+		let kitty_id: u32 = unhashed::get_or_default(NEXT_ID_KEY);
+		unhashed::put(NEXT_ID_KEY, &(kitty_id + 1));
+		Self::mint(kitty_id, *owner);
+		kitty_id
+	}
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities =
+		frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
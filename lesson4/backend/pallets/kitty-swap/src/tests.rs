@@ -0,0 +1,129 @@
+use super::*;
+
+use frame_support::{assert_noop, assert_ok};
+use mock::{new_test_ext, Event as TestEvent, KittySwap, MockKitties, Origin, System, Test};
+use pallet_kitties::{KittyInspect, KittyTransfer};
+use sp_runtime::DispatchError;
+
+const ALICE: u64 = 1;
+const BOB: u64 = 2;
+const CHARLIE: u64 = 3;
+
+#[test]
+fn it_should_work_for_swap_kitties() {
+	new_test_ext().execute_with(|| {
+		MockKitties::mint(0, ALICE);
+		MockKitties::mint(1, BOB);
+
+		assert_ok!(KittySwap::propose_swap(Origin::signed(ALICE), 0, 1));
+		assert_eq!(KittySwap::swaps(0), Some((ALICE, 1)));
+		System::assert_last_event(TestEvent::KittySwap(Event::SwapProposed(ALICE, 0, 1)));
+
+		//只有想要的kitty的拥有者可以接受
+		assert_noop!(KittySwap::accept_swap(Origin::signed(CHARLIE), 0), Error::<Test>::NotOwner);
+
+		assert_ok!(KittySwap::accept_swap(Origin::signed(BOB), 0));
+		assert_eq!(MockKitties::owner(&0), Some(BOB));
+		assert_eq!(MockKitties::owner(&1), Some(ALICE));
+		assert_eq!(KittySwap::swaps(0), None);
+		System::assert_last_event(TestEvent::KittySwap(Event::SwapAccepted(ALICE, BOB, 0, 1)));
+	});
+}
+
+#[test]
+fn propose_swap_should_fail_when_invalid() {
+	new_test_ext().execute_with(|| {
+		MockKitties::mint(0, ALICE);
+		MockKitties::mint(1, BOB);
+		MockKitties::mint(2, ALICE);
+
+		assert_noop!(KittySwap::propose_swap(Origin::signed(BOB), 0, 1), Error::<Test>::NotOwner);
+		assert_noop!(
+			KittySwap::propose_swap(Origin::signed(ALICE), 0, 9),
+			Error::<Test>::InvalidKittyId
+		);
+		assert_noop!(
+			KittySwap::propose_swap(Origin::signed(ALICE), 0, 2),
+			Error::<Test>::SwapWithSelf
+		);
+
+		MockKitties::lock(0);
+		assert_noop!(
+			KittySwap::propose_swap(Origin::signed(ALICE), 0, 1),
+			Error::<Test>::NotTransferable
+		);
+
+		assert_ok!(KittySwap::propose_swap(Origin::signed(ALICE), 2, 1));
+		assert_noop!(
+			KittySwap::propose_swap(Origin::signed(ALICE), 2, 1),
+			Error::<Test>::SwapAlreadyExist
+		);
+
+		//只有提出者可以取消
+		assert_noop!(KittySwap::cancel_swap(Origin::signed(BOB), 2), Error::<Test>::NotOwner);
+		assert_ok!(KittySwap::cancel_swap(Origin::signed(ALICE), 2));
+		System::assert_last_event(TestEvent::KittySwap(Event::SwapCancelled(ALICE, 2)));
+		assert_noop!(KittySwap::cancel_swap(Origin::signed(ALICE), 2), Error::<Test>::SwapNotFound);
+	});
+}
+
+#[test]
+fn accept_swap_should_fail_when_not_transferable() {
+	new_test_ext().execute_with(|| {
+		MockKitties::mint(0, ALICE);
+		MockKitties::mint(1, BOB);
+		assert_ok!(KittySwap::propose_swap(Origin::signed(ALICE), 0, 1));
+
+		//想要的kitty不能转移时整个交换回滚
+		MockKitties::lock(1);
+		assert_noop!(
+			KittySwap::accept_swap(Origin::signed(BOB), 0),
+			DispatchError::Other("kitty cannot be transferred")
+		);
+		assert_eq!(MockKitties::owner(&0), Some(ALICE));
+		assert_eq!(KittySwap::swaps(0), Some((ALICE, 1)));
+	});
+}
+
+#[test]
+fn accept_swap_should_remove_stale_swap() {
+	new_test_ext().execute_with(|| {
+		MockKitties::mint(0, ALICE);
+		MockKitties::mint(1, BOB);
+		assert_ok!(KittySwap::propose_swap(Origin::signed(ALICE), 0, 1));
+
+		//没有经过转移通知，提出者的kitty已经属于别人
+		MockKitties::mint(0, CHARLIE);
+		assert_ok!(KittySwap::accept_swap(Origin::signed(BOB), 0));
+		assert_eq!(KittySwap::swaps(0), None);
+		assert_eq!(MockKitties::owner(&0), Some(CHARLIE));
+		assert_eq!(MockKitties::owner(&1), Some(BOB));
+		System::assert_last_event(TestEvent::KittySwap(Event::SwapInvalidated(ALICE, 0)));
+	});
+}
+
+#[test]
+fn transfer_or_burn_should_remove_swap() {
+	new_test_ext().execute_with(|| {
+		MockKitties::mint(0, ALICE);
+		MockKitties::mint(1, BOB);
+		MockKitties::mint(2, ALICE);
+		assert_ok!(KittySwap::propose_swap(Origin::signed(ALICE), 0, 1));
+		assert_ok!(KittySwap::propose_swap(Origin::signed(ALICE), 2, 1));
+
+		assert_ok!(<MockKitties as KittyTransfer<u64>>::transfer(&0, &CHARLIE));
+		assert_eq!(KittySwap::swaps(0), None);
+		System::assert_last_event(TestEvent::KittySwap(Event::SwapInvalidated(ALICE, 0)));
+
+		MockKitties::burn(2);
+		assert_eq!(KittySwap::swaps(2), None);
+		System::assert_last_event(TestEvent::KittySwap(Event::SwapInvalidated(ALICE, 2)));
+
+		//想要的kitty提出的交换在接受时也会失效
+		assert_ok!(KittySwap::propose_swap(Origin::signed(CHARLIE), 0, 1));
+		assert_ok!(KittySwap::propose_swap(Origin::signed(BOB), 1, 0));
+		assert_ok!(KittySwap::accept_swap(Origin::signed(BOB), 0));
+		assert_eq!(KittySwap::swaps(1), None);
+		System::assert_has_event(TestEvent::KittySwap(Event::SwapInvalidated(BOB, 1)));
+	});
+}
@@ -0,0 +1,110 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Weights for pallet_kitty_swap
+//!
+//! These weights are hand estimates, not benchmark output. The storage reads and writes follow
+//! the worst cases set up in `benchmarking.rs`, but the base and per-item times have not been
+//! measured. Regenerate this file with `./scripts/benchmark.sh pallet_kitty_swap`, run on
+//! reference hardware, before relying on them.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_kitty_swap.
+pub trait WeightInfo {
+	fn propose_swap() -> Weight;
+	fn cancel_swap() -> Weight;
+	fn accept_swap() -> Weight;
+}
+
+/// Hand-estimated weights for pallet_kitty_swap, see the module docs.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: KittySwap Swaps (r:1 w:1)
+	// Storage: Kitties KittyOwner (r:3 w:0)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	fn propose_swap() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: KittySwap Swaps (r:1 w:1)
+	fn cancel_swap() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: KittySwap Swaps (r:3 w:3)
+	// Storage: Kitties KittyOwner (r:4 w:2)
+	// Storage: Kitties Auctions (r:2 w:0)
+	// Storage: Kitties KittyHolder (r:2 w:0)
+	// Storage: Kitties KittyMetadataOf (r:2 w:0)
+	// Storage: Kitties KittyDeposit (r:2 w:2)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Kitties OwnedKitties (r:2 w:4)
+	// Storage: Kitties OwnedKittiesCount (r:4 w:4)
+	// Storage: Kitties KittiesForSale (r:0 w:2)
+	// Storage: Kitties SiringOffers (r:0 w:2)
+	// Storage: Kitties KittyApprovals (r:0 w:2)
+	fn accept_swap() -> Weight {
+		(140_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(23 as Weight))
+			.saturating_add(T::DbWeight::get().writes(23 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: KittySwap Swaps (r:1 w:1)
+	// Storage: Kitties KittyOwner (r:3 w:0)
+	// Storage: Kitties Auctions (r:1 w:0)
+	// Storage: Kitties KittyHolder (r:1 w:0)
+	fn propose_swap() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: KittySwap Swaps (r:1 w:1)
+	fn cancel_swap() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: KittySwap Swaps (r:3 w:3)
+	// Storage: Kitties KittyOwner (r:4 w:2)
+	// Storage: Kitties Auctions (r:2 w:0)
+	// Storage: Kitties KittyHolder (r:2 w:0)
+	// Storage: Kitties KittyMetadataOf (r:2 w:0)
+	// Storage: Kitties KittyDeposit (r:2 w:2)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Kitties OwnedKitties (r:2 w:4)
+	// Storage: Kitties OwnedKittiesCount (r:4 w:4)
+	// Storage: Kitties KittiesForSale (r:0 w:2)
+	// Storage: Kitties SiringOffers (r:0 w:2)
+	// Storage: Kitties KittyApprovals (r:0 w:2)
+	fn accept_swap() -> Weight {
+		(140_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(23 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(23 as Weight))
+	}
+}
@@ -65,6 +65,9 @@ pub type Balance = u128;
 /// Index of a transaction in the chain.
 pub type Index = u32;
 
+/// Index of a kitty.
+pub type KittyIndex = u32;
+
 /// A hash of some data used by the chain.
 pub type Hash = sp_core::H256;
 
@@ -328,12 +331,56 @@ impl pallet_poe::Config for Runtime {
 
 impl pallet_kitties::Config for Runtime {
 	type Event = Event;
+	//Aura 链没有 VRF 随机数，出块者可以影响这个随机数，所以dna还要混合创建者承诺的秘密
 	type Randomness = RandomnessCollectiveFlip;
-	type KittyIndex = u32;
+	type KittyIndex = KittyIndex;
 	type Currency = Balances;
 	type MaxKittyIndex = ConstU32<512>;
 	type KittyPrice = ConstU128<512>;
+	type BreedCooldown = ConstU32<{ 10 * MINUTES }>;
+	type MaxChildren = ConstU32<16>;
+	type DnaRevealDelay = ConstU32<3>;
+	type MaxRevealsPerBlock = ConstU32<64>;
+	type BurnCooldown = ConstU32<DAYS>;
+	type MaxAuctionDuration = ConstU32<{ 7 * DAYS }>;
+	type MaxAuctionsPerBlock = ConstU32<16>;
+	type MaxLoanDuration = ConstU32<{ 7 * DAYS }>;
+	type MaxLoansPerBlock = ConstU32<16>;
+	type MaxNameLength = ConstU32<32>;
+	type MaxMetadataUriLength = ConstU32<128>;
+	type MetadataDepositPerByte = ConstU128<1>;
 	type AuthorityId = pallet_kitties::crypto::KittiesAuthId;
+	type MaxAuthorities = ConstU32<16>;
+	type UnsignedPriority = ConstU64<{ u64::MAX / 2 }>;
+	type OnKittyTransfer = KittySwap;
+	type WeightInfo = pallet_kitties::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_kitty_swap::Config for Runtime {
+	type Event = Event;
+	type KittyId = KittyIndex;
+	type Kitties = Kitties;
+	type WeightInfo = pallet_kitty_swap::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = KittySwapBenchmarkHelper;
+}
+
+//基准测试中通过 Kitties 创建真实的kitty
+#[cfg(feature = "runtime-benchmarks")]
+pub struct KittySwapBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_kitty_swap::BenchmarkHelper<AccountId, KittyIndex> for KittySwapBenchmarkHelper {
+	fn mint(owner: &AccountId) -> KittyIndex {
+		use frame_support::traits::Currency;
+
+		//足够锁定收到的kitty的押金
+		Balances::make_free_balance_be(owner, 1_000_000_000_000);
+		let kitty_id = Kitties::next_kitty_id();
+		Kitties::create(Origin::signed(owner.clone()), Default::default())
+			.expect("owner is funded and below the kitty limit");
+		kitty_id
+	}
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -355,6 +402,7 @@ construct_runtime!(
 		TemplateModule: pallet_template,
 		PoeModule: pallet_poe,
 		Kitties: pallet_kitties,
+		KittySwap: pallet_kitty_swap,
 	}
 );
 
@@ -398,6 +446,8 @@ mod benches {
 		[frame_benchmarking, BaselineBench::<Runtime>]
 		[frame_system, SystemBench::<Runtime>]
 		[pallet_balances, Balances]
+		[pallet_kitties, Kitties]
+		[pallet_kitty_swap, KittySwap]
 		[pallet_timestamp, Timestamp]
 		[pallet_template, TemplateModule]
 	);
@@ -534,6 +584,30 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_rpc_runtime_api::KittiesApi<Block, AccountId, KittyIndex, BlockNumber> for Runtime {
+		fn kitties_of(
+			account: AccountId,
+			start_after: Option<KittyIndex>,
+			limit: u32,
+		) -> Vec<KittyIndex> {
+			Kitties::kitties_of(&account, start_after, limit)
+		}
+
+		fn owned_count(account: AccountId) -> u32 {
+			Kitties::owned_count(account)
+		}
+
+		fn kitty_details(
+			kitty_id: KittyIndex,
+		) -> Option<pallet_kitties::KittyInfo<AccountId, KittyIndex, BlockNumber>> {
+			Kitties::kitty_details(kitty_id)
+		}
+
+		fn total_supply() -> KittyIndex {
+			Kitties::total_supply()
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
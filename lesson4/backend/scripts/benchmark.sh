@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+# This script is meant to be run on Unix/Linux based systems
+# Usage: ./scripts/benchmark.sh pallet_kitties
+set -e
+
+PALLET=${1:?"usage: $0 <pallet, e.g. pallet_kitties>"}
+# pallet_kitties -> pallets/kitties, pallet_kitty_swap -> pallets/kitty-swap
+DIR=pallets/$(echo "${PALLET#pallet_}" | tr '_' '-')
+
+echo "*** Building node with runtime benchmarks"
+
+cargo build --release --features runtime-benchmarks
+
+echo "*** Benchmarking $PALLET into $DIR/src/weights.rs"
+
+./target/release/node-template benchmark pallet \
+	--chain dev \
+	--execution wasm \
+	--wasm-execution compiled \
+	--pallet "$PALLET" \
+	--extrinsic '*' \
+	--steps 50 \
+	--repeat 20 \
+	--output "$DIR/src/weights.rs" \
+	--template .maintain/frame-weight-template.hbs
@@ -5,6 +5,7 @@ import { useSubstrateState } from './substrate-lib';
 import { TxButton } from './substrate-lib/components';
 
 import KittyCards from './KittyCards';
+import { newDnaCommitment } from './kittySecrets';
 
 export default function Kitties(props) {
   const { api, keyring, currentAccount } = useSubstrateState();
@@ -12,6 +13,7 @@ export default function Kitties(props) {
   const [kittyIndexes, setKittyIndexes] = useState([]);
   const [kitties, setKitties] = useState([]);
   const [status, setStatus] = useState('');
+  const [dnaCommitment, setDnaCommitment] = useState(newDnaCommitment);
 
   useEffect(() => {
     const fetchKittyIndexes = async () => {
@@ -37,10 +39,20 @@ export default function Kitties(props) {
       const kittyDNAs = await api.query.kittiesModule.kitties.multi(
         kittyIndexes
       );
+      const commitments = await api.query.kittiesModule.dnaCommitments.multi(
+        kittyIndexes
+      );
+      const drawnDNAs = await api.query.kittiesModule.drawnDna.multi(
+        kittyIndexes
+      );
       const kitties = kittyIndexes.map(kittyIndex => ({
         id: kittyIndex,
         dna: kittyDNAs[kittyIndex].value,
         owner: owners[kittyIndex].value.toJSON(),
+        commitment: commitments[kittyIndex].isSome
+          ? commitments[kittyIndex].unwrap().toHex()
+          : null,
+        seedDrawn: drawnDNAs[kittyIndex].isSome,
       }));
       setKitties(kitties);
     };
@@ -50,7 +62,7 @@ export default function Kitties(props) {
     return () => {
       unsub && unsub();
     };
-  }, [api, keyring, kittyIndexes, setKitties]);
+  }, [api, keyring, kittyIndexes, status, setKitties]);
 
   return (
     <Grid.Column width={16}>
@@ -67,11 +79,12 @@ export default function Kitties(props) {
             label="创建小毛孩"
             type="SIGNED-TX"
             setStatus={setStatus}
+            txOnClickHandler={() => setDnaCommitment(newDnaCommitment())}
             attrs={{
               palletRpc: 'kittiesModule',
               callable: 'create',
-              inputParams: [],
-              paramFields: [],
+              inputParams: [dnaCommitment],
+              paramFields: [true],
             }}
           />
         </Form.Field>
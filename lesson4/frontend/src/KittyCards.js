@@ -10,6 +10,7 @@ import {
 } from 'semantic-ui-react';
 
 import KittyAvatar from './KittyAvatar';
+import { secretFor } from './kittySecrets';
 import { TxButton } from './substrate-lib/components';
 
 // --- About Modal ---
@@ -72,6 +73,34 @@ const TransferModal = props => {
   );
 };
 
+// --- About Reveal ---
+
+// 揭示区块已经抽取随机数，用创建时保存的秘密揭示基因
+const RevealButton = props => {
+  const { kitty, accountPair, setStatus } = props;
+  const secret =
+    kitty.seedDrawn && kitty.commitment && secretFor(kitty.commitment);
+
+  if (!secret) {
+    return null;
+  }
+
+  return (
+    <TxButton
+      accountPair={accountPair}
+      label="揭示基因"
+      type="SIGNED-TX"
+      setStatus={setStatus}
+      attrs={{
+        palletRpc: 'kittiesModule',
+        callable: 'revealDna',
+        inputParams: [kitty.id, secret],
+        paramFields: [true, true],
+      }}
+    />
+  );
+};
+
 // --- About Kitty Card ---
 
 const KittyCard = props => {
@@ -104,6 +133,11 @@ const KittyCard = props => {
         </Card.Description>
       </Card.Content>
       <Card.Content extra style={{ textAlign: 'center' }}>
+        <RevealButton
+          kitty={kitty}
+          accountPair={accountPair}
+          setStatus={setStatus}
+        />
         {owner === accountPair.address ? (
           <TransferModal
             kitty={kitty}
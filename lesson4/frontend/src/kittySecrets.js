@@ -0,0 +1,21 @@
+import { blake2AsHex, randomAsHex } from '@polkadot/util-crypto';
+
+// 创建小毛孩时承诺的秘密保存在浏览器里，揭示基因时需要提供
+const STORAGE_KEY = 'kittyDnaSecrets';
+
+const loadSecrets = () =>
+  JSON.parse(localStorage.getItem(STORAGE_KEY) || '{}');
+
+// 生成新的秘密并保存，返回提交到链上的承诺
+export const newDnaCommitment = () => {
+  const secret = randomAsHex(32);
+  const commitment = blake2AsHex(secret, 256);
+  localStorage.setItem(
+    STORAGE_KEY,
+    JSON.stringify({ ...loadSecrets(), [commitment]: secret })
+  );
+  return commitment;
+};
+
+// 承诺对应的秘密，不是在这个浏览器创建的返回 undefined
+export const secretFor = commitment => loadSecrets()[commitment];
@@ -0,0 +1,167 @@
+//! 在链下构建 poe 的 Merkle 树和包含证明
+//!
+//! 哈希规则直接使用 `pallet_poe::merkle` 中的函数，保证构建出的根和证明能通过链上的
+//! `verify_inclusion` 验证：先用 `MerkleTree::root` 和 `MerkleTree::leaf_count` 调用
+//! `anchor_root`，再把 `MerkleTree::proof` 交给需要验证的一方。
+
+use pallet_poe::merkle::{hash_leaf, hash_node};
+
+pub use pallet_poe::{merkle::verify, Claim, HashAlgorithm, MerkleProof};
+
+pub struct MerkleTree {
+	algorithm: HashAlgorithm,
+	//第一层是叶子哈希，最后一层只有根
+	levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+	//用文档摘要作为叶子构建树，没有叶子或叶子数量超过u32时返回None
+	pub fn new(algorithm: HashAlgorithm, leaves: &[[u8; 32]]) -> Option<Self> {
+		if leaves.is_empty() || u32::try_from(leaves.len()).is_err() {
+			return None
+		}
+
+		let mut levels =
+			vec![leaves.iter().map(|leaf| hash_leaf(algorithm, leaf)).collect::<Vec<_>>()];
+
+		//两两合并，落单的最后一个节点直接升到上一层
+		while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+			let next = level
+				.chunks(2)
+				.map(|pair| match pair {
+					[left, right] => hash_node(algorithm, left, right),
+					[single] => *single,
+					_ => unreachable!("chunks(2) yields one or two nodes"),
+				})
+				.collect();
+			levels.push(next);
+		}
+
+		Some(MerkleTree { algorithm, levels })
+	}
+
+	//先用同一个算法计算每份文档的摘要，再构建树
+	pub fn from_contents<C: AsRef<[u8]>>(algorithm: HashAlgorithm, contents: &[C]) -> Option<Self> {
+		let leaves: Vec<[u8; 32]> =
+			contents.iter().map(|content| algorithm.hash(content.as_ref())).collect();
+		Self::new(algorithm, &leaves)
+	}
+
+	//作为存证上链的根
+	pub fn root(&self) -> Claim {
+		let root = self.levels.last().and_then(|level| level.first()).expect("tree is not empty");
+		Claim { algorithm: self.algorithm, digest: *root }
+	}
+
+	pub fn leaf_count(&self) -> u32 {
+		self.levels[0].len() as u32
+	}
+
+	//第 leaf_index 个叶子的包含证明，超出范围时返回None
+	pub fn proof(&self, leaf_index: u32) -> Option<MerkleProof> {
+		if leaf_index >= self.leaf_count() {
+			return None
+		}
+
+		//奇数位置的兄弟在左边，偶数位置的兄弟在右边，落单的节点没有兄弟
+		let mut index = leaf_index as usize;
+		let mut siblings = Vec::new();
+		for level in &self.levels[..self.levels.len() - 1] {
+			if let Some(sibling) = level.get(index ^ 1) {
+				siblings.push(*sibling);
+			}
+			index /= 2;
+		}
+
+		Some(MerkleProof { leaf_index, siblings })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const ALGORITHMS: [HashAlgorithm; 3] =
+		[HashAlgorithm::Blake2_256, HashAlgorithm::Sha2_256, HashAlgorithm::Keccak256];
+
+	fn leaves(n: u32) -> Vec<[u8; 32]> {
+		(0..n).map(|i| HashAlgorithm::Blake2_256.hash(&i.to_le_bytes())).collect()
+	}
+
+	#[test]
+	fn every_proof_verifies_against_pallet() {
+		for algorithm in ALGORITHMS {
+			for n in 1..=17 {
+				let leaves = leaves(n);
+				let tree = MerkleTree::new(algorithm, &leaves).unwrap();
+				assert_eq!(tree.leaf_count(), n);
+				assert_eq!(tree.root().algorithm, algorithm);
+
+				for (i, leaf) in leaves.iter().enumerate() {
+					let proof = tree.proof(i as u32).unwrap();
+					assert!(verify(&tree.root(), leaf, n, &proof), "{:?} {} {}", algorithm, n, i);
+				}
+				assert!(tree.proof(n).is_none());
+			}
+		}
+	}
+
+	#[test]
+	fn root_follows_tree_rules() {
+		let algorithm = HashAlgorithm::Sha2_256;
+		let [a, b, c] = [[1u8; 32], [2u8; 32], [3u8; 32]];
+
+		//只有一个叶子时根就是叶子哈希
+		let tree = MerkleTree::new(algorithm, &[a]).unwrap();
+		assert_eq!(tree.root().digest, hash_leaf(algorithm, &a));
+		assert!(tree.proof(0).unwrap().siblings.is_empty());
+
+		//落单的第三个叶子直接升层
+		let tree = MerkleTree::new(algorithm, &[a, b, c]).unwrap();
+		let ab = hash_node(algorithm, &hash_leaf(algorithm, &a), &hash_leaf(algorithm, &b));
+		assert_eq!(tree.root().digest, hash_node(algorithm, &ab, &hash_leaf(algorithm, &c)));
+		assert_eq!(tree.proof(2).unwrap().siblings, vec![ab]);
+
+		assert!(MerkleTree::new(algorithm, &[]).is_none());
+	}
+
+	#[test]
+	fn tampered_proof_fails() {
+		let leaves = leaves(5);
+		let tree = MerkleTree::new(HashAlgorithm::Blake2_256, &leaves).unwrap();
+		let root = tree.root();
+		let proof = tree.proof(1).unwrap();
+
+		//叶子不在树中
+		assert!(!verify(&root, &[0u8; 32], 5, &proof));
+
+		//位置不对
+		let wrong_index = MerkleProof { leaf_index: 0, ..proof.clone() };
+		assert!(!verify(&root, &leaves[1], 5, &wrong_index));
+
+		//兄弟节点多了或少了
+		let mut extra = proof.clone();
+		extra.siblings.push([0u8; 32]);
+		assert!(!verify(&root, &leaves[1], 5, &extra));
+		let mut missing = proof.clone();
+		missing.siblings.pop();
+		assert!(!verify(&root, &leaves[1], 5, &missing));
+
+		//叶子数量不对：第5个叶子在5个叶子的树中没有兄弟节点，在6个叶子的树中有
+		let last = tree.proof(4).unwrap();
+		assert!(verify(&root, &leaves[4], 5, &last));
+		assert!(!verify(&root, &leaves[4], 6, &last));
+
+		//算法不对
+		let other = Claim { algorithm: HashAlgorithm::Keccak256, ..root };
+		assert!(!verify(&other, &leaves[1], 5, &proof));
+	}
+
+	#[test]
+	fn from_contents_hashes_documents() {
+		let contents = [b"a".to_vec(), b"b".to_vec()];
+		let tree = MerkleTree::from_contents(HashAlgorithm::Keccak256, &contents).unwrap();
+		let leaf = HashAlgorithm::Keccak256.hash(b"b");
+		assert!(verify(&tree.root(), &leaf, 2, &tree.proof(1).unwrap()));
+	}
+}
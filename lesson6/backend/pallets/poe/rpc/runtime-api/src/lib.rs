@@ -0,0 +1,17 @@
+//! poe 的 runtime api，供 rpc 调用
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+
+pub use pallet_poe::{Claim, HashAlgorithm, MerkleProof};
+
+sp_api::decl_runtime_apis! {
+	pub trait PoeApi {
+		//按指定算法计算内容的存证，把结果提交给 create_claim，内容本身不需要上链
+		fn create_claim_from_content(algorithm: HashAlgorithm, content: Vec<u8>) -> Claim;
+
+		//叶子是否包含在通过 anchor_root 上链的 Merkle 根中
+		fn verify_inclusion(root: Claim, leaf: [u8; 32], proof: MerkleProof) -> bool;
+	}
+}
@@ -0,0 +1,95 @@
+//! poe 的 rpc 方法：poe_createClaimFromContent 和 poe_verifyInclusion
+
+use std::sync::Arc;
+
+use jsonrpsee::{
+	core::{async_trait, Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_poe_rpc_runtime_api::{Claim, HashAlgorithm, MerkleProof, PoeApi as PoeRuntimeApi};
+
+#[rpc(client, server)]
+pub trait PoeApi<BlockHash> {
+	//按指定算法计算内容的存证，内容只发给本地节点，不会上链
+	#[method(name = "poe_createClaimFromContent")]
+	fn create_claim_from_content(
+		&self,
+		algorithm: HashAlgorithm,
+		content: Vec<u8>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Claim>;
+
+	//叶子是否包含在通过 anchor_root 上链的 Merkle 根中
+	#[method(name = "poe_verifyInclusion")]
+	fn verify_inclusion(
+		&self,
+		root: Claim,
+		leaf: [u8; 32],
+		proof: MerkleProof,
+		at: Option<BlockHash>,
+	) -> RpcResult<bool>;
+}
+
+/// Provides RPC methods to query the poe pallet.
+pub struct Poe<C, Block> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block> Poe<C, Block> {
+	/// Creates a new instance of the Poe RPC helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+//调用runtime出错时返回的错误码
+const RUNTIME_ERROR: i32 = 1;
+
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		RUNTIME_ERROR,
+		"Unable to query poe from runtime",
+		Some(format!("{:?}", err)),
+	))
+	.into()
+}
+
+#[async_trait]
+impl<C, Block> PoeApiServer<<Block as BlockT>::Hash> for Poe<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: PoeRuntimeApi<Block>,
+{
+	fn create_claim_from_content(
+		&self,
+		algorithm: HashAlgorithm,
+		content: Vec<u8>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Claim> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.create_claim_from_content(&at, algorithm, content)
+			.map_err(runtime_error_into_rpc_err)
+	}
+
+	fn verify_inclusion(
+		&self,
+		root: Claim,
+		leaf: [u8; 32],
+		proof: MerkleProof,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<bool> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.verify_inclusion(&at, root, leaf, proof).map_err(runtime_error_into_rpc_err)
+	}
+}
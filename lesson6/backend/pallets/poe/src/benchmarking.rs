@@ -1,6 +1,11 @@
 use crate::*;
 use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::{
+	sp_runtime::traits::Saturating,
+	traits::{Currency, ReservableCurrency},
+};
 use frame_system::RawOrigin;
+use sp_std::vec;
 
 const SEED: u32 = 0;
 
@@ -8,21 +13,84 @@ fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
 	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
 }
 
-fn insert_claim<T: Config>(claim: &Vec<u8>, sender: &T::AccountId) {
-	let bounded_claim =
-		BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone()).expect("claim too long");
-	Proofs::<T>::insert(
-		&bounded_claim,
-		(sender.clone(), frame_system::Pallet::<T>::block_number()),
-	);
+//给账户足够锁定多个存证押金和最长元数据押金的余额
+fn fund<T: Config>(who: &T::AccountId) {
+	fund_for::<T>(who, 0);
+}
+
+//在 fund 的基础上再给账户足够锁定 n 个存证的余额
+fn fund_for<T: Config>(who: &T::AccountId, n: u32) {
+	let deposit = Pallet::<T>::claim_deposit()
+		.saturating_add(Pallet::<T>::metadata_deposit(Pallet::<T>::max_metadata_bytes()));
+	T::Currency::make_free_balance_be(who, deposit.saturating_mul(n.saturating_add(100).into()));
+}
+
+//直接写入存储创建存证，并锁定押金
+fn insert_claim<T: Config>(claim: &Claim, sender: &T::AccountId) {
+	let deposit = Pallet::<T>::claim_deposit();
+	T::Currency::reserve(sender, deposit).expect("sender is funded");
+	Deposits::<T>::insert(claim, deposit);
+	Proofs::<T>::insert(claim, (sender.clone(), frame_system::Pallet::<T>::block_number()));
+}
+
+//给存证设置最长的元数据，转移和撤销时需要一起处理押金
+fn set_max_metadata<T: Config>(claim: &Claim, owner: &T::AccountId) {
+	set_metadata::<T>(claim, owner, Pallet::<T>::max_metadata_bytes());
+}
+
+//给存证设置 b 字节的元数据，并锁定押金
+fn set_metadata<T: Config>(claim: &Claim, owner: &T::AccountId, b: u32) {
+	let (description, content_type, uri) = metadata_of_len::<T>(b);
+	let description: BoundedVec<u8, T::MaxDescriptionLength> =
+		description.try_into().expect("within max length");
+	let content_type: BoundedVec<u8, T::MaxContentTypeLength> =
+		content_type.try_into().expect("within max length");
+	let uri: BoundedVec<u8, T::MaxUriLength> = uri.try_into().expect("within max length");
+
+	let deposit = Pallet::<T>::metadata_deposit(b);
+	T::Currency::reserve(owner, deposit).expect("owner is funded");
+
+	ClaimMetadataOf::<T>::insert(claim, ClaimMetadata { description, content_type, uri, deposit });
+}
+
+//把 b 字节的元数据依次分给 claims，每个存证最多分到最长的元数据，分不完的部分忽略
+fn spread_metadata<T: Config>(claims: &[Claim], owner: &T::AccountId, b: u32) {
+	let mut remaining = b;
+	for claim in claims {
+		let bytes = remaining.min(Pallet::<T>::max_metadata_bytes());
+		set_metadata::<T>(claim, owner, bytes);
+		remaining -= bytes;
+	}
+}
+
+//批量调用的 n 个不同存证
+fn claims<T: Config>(n: u32) -> BoundedVec<Claim, T::MaxClaimsPerBatch> {
+	(0..n)
+		.map(|i| Claim::from_content(HashAlgorithm::Blake2_256, &i.to_le_bytes()))
+		.collect::<Vec<_>>()
+		.try_into()
+		.expect("within max claims per batch")
+}
+
+//总长度为 b 字节的元数据，依次填满描述、内容类型和外部地址
+fn metadata_of_len<T: Config>(b: u32) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+	let description_len = b.min(T::MaxDescriptionLength::get());
+	let content_type_len = (b - description_len).min(T::MaxContentTypeLength::get());
+	let uri_len = b - description_len - content_type_len;
+
+	(
+		vec![b'd'; description_len as usize],
+		vec![b't'; content_type_len as usize],
+		vec![b'u'; uri_len as usize],
+	)
 }
 
 benchmarks! {
 	create_claim {
-		let d in 0..T::MaxClaimLength::get();
-		let claim = vec![0; d as usize];
+		let claim = Claim::from_content(HashAlgorithm::Blake2_256, b"poe");
 		let caller: T::AccountId = whitelisted_caller();
-	} : _(RawOrigin::Signed(caller.clone()), claim.clone())
+		fund::<T>(&caller);
+	} : _(RawOrigin::Signed(caller.clone()), claim)
 	verify {
 		assert_last_event::<T>(
 			Event::ClaimCreated(caller, claim).into()
@@ -30,31 +98,123 @@ benchmarks! {
 	}
 
 	transfer_claim {
-		let d in 0..T::MaxClaimLength::get();
-		let claim = vec![0; d as usize];
+		let claim = Claim::from_content(HashAlgorithm::Blake2_256, b"poe");
 		let caller: T::AccountId = whitelisted_caller();
 		let dest: T::AccountId = account("dest", 0, SEED);
+		fund::<T>(&caller);
+		//押金转给新的拥有者时要求它的账户已经存在
+		fund::<T>(&dest);
 
 		insert_claim::<T>(&claim, &caller);
-	} : _(RawOrigin::Signed(caller.clone()), claim.clone(), dest.clone())
+		set_max_metadata::<T>(&claim, &caller);
+	} : _(RawOrigin::Signed(caller.clone()), claim, dest.clone())
 	verify {
 		assert_last_event::<T>(
-			Event::ClaimChanged(caller.clone(), claim.clone()).into()
+			Event::ClaimChanged(caller.clone(), claim).into()
 		);
 	}
 
 	revoke_claim {
-		let d in 0..T::MaxClaimLength::get();
-		let claim = vec![0; d as usize];
+		let claim = Claim::from_content(HashAlgorithm::Blake2_256, b"poe");
 		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
 
 		insert_claim::<T>(&claim, &caller);
-	} : _(RawOrigin::Signed(caller.clone()), claim.clone())
+		set_max_metadata::<T>(&claim, &caller);
+	} : _(RawOrigin::Signed(caller.clone()), claim)
 	verify {
 		assert_last_event::<T>(
 			Event::ClaimRevoked(caller, claim).into()
 		);
 	}
 
+	//第一次设置 b 字节的元数据，需要锁定全部押金
+	set_claim_metadata {
+		let b in 0 .. Pallet::<T>::max_metadata_bytes();
+
+		let claim = Claim::from_content(HashAlgorithm::Blake2_256, b"poe");
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+
+		insert_claim::<T>(&claim, &caller);
+		let (description, content_type, uri) = metadata_of_len::<T>(b);
+	} : _(
+		RawOrigin::Signed(caller.clone()),
+		claim,
+		description.clone(),
+		content_type.clone(),
+		uri.clone()
+	)
+	verify {
+		assert_last_event::<T>(
+			Event::ClaimMetadataSet(caller, claim, description, content_type, uri).into()
+		);
+	}
+
+	anchor_root {
+		let root = Claim::from_content(HashAlgorithm::Blake2_256, b"root");
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+	} : _(RawOrigin::Signed(caller.clone()), root, 1_000)
+	verify {
+		assert_last_event::<T>(
+			Event::RootAnchored(caller, root, 1_000).into()
+		);
+	}
+
+	//批量新建 c 个存证
+	create_claims {
+		let c in 1 .. T::MaxClaimsPerBatch::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		fund_for::<T>(&caller, c);
+		let claims = claims::<T>(c);
+	} : _(RawOrigin::Signed(caller.clone()), claims.clone())
+	verify {
+		for claim in claims {
+			assert!(Proofs::<T>::contains_key(&claim));
+		}
+	}
+
+	//批量撤销 c 个存证，它们的元数据共 b 字节
+	revoke_claims {
+		let c in 1 .. T::MaxClaimsPerBatch::get();
+		let b in 0 .. T::MaxClaimsPerBatch::get() * Pallet::<T>::max_metadata_bytes();
+
+		let caller: T::AccountId = whitelisted_caller();
+		fund_for::<T>(&caller, c);
+		let claims = claims::<T>(c);
+		for claim in claims.iter() {
+			insert_claim::<T>(claim, &caller);
+		}
+		spread_metadata::<T>(&claims, &caller, b);
+	} : _(RawOrigin::Signed(caller.clone()), claims.clone())
+	verify {
+		for claim in claims {
+			assert!(!Proofs::<T>::contains_key(&claim));
+		}
+	}
+
+	//批量转移 c 个存证，它们的元数据共 b 字节
+	transfer_claims {
+		let c in 1 .. T::MaxClaimsPerBatch::get();
+		let b in 0 .. T::MaxClaimsPerBatch::get() * Pallet::<T>::max_metadata_bytes();
+
+		let caller: T::AccountId = whitelisted_caller();
+		let dest: T::AccountId = account("dest", 0, SEED);
+		fund_for::<T>(&caller, c);
+		fund::<T>(&dest);
+		let claims = claims::<T>(c);
+		for claim in claims.iter() {
+			insert_claim::<T>(claim, &caller);
+		}
+		spread_metadata::<T>(&claims, &caller, b);
+	} : _(RawOrigin::Signed(caller.clone()), claims.clone(), dest.clone())
+	verify {
+		for claim in claims {
+			assert_eq!(Proofs::<T>::get(&claim).map(|(owner, _)| owner), Some(dest.clone()));
+		}
+	}
+
 	impl_benchmark_test_suite!(PoeModule, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -12,6 +12,8 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod merkle;
+pub mod migrations;
 pub mod weights;
 
 #[frame_support::pallet] //开发pellet所用的宏
@@ -22,121 +24,505 @@ pub mod pallet {
 	pub use frame_support::pallet_prelude::*; //比如get接口
 	pub use frame_system::pallet_prelude::*; //比如一些方便签名和验证的方法
 	pub use sp_std::prelude::*; //引入要使用的vector
+	use frame_support::{
+		sp_runtime::traits::{Saturating, Zero},
+		traits::{BalanceStatus, Currency, OnRuntimeUpgrade, ReservableCurrency},
+		transactional,
+	};
+
+	#[cfg(feature = "std")]
+	use serde::{Deserialize, Serialize};
+
+	pub use crate::merkle::MerkleProof;
+
+	//押金的类型
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	//计算存证摘要所用的哈希算法
+	#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum HashAlgorithm {
+		Blake2_256,
+		Sha2_256,
+		Keccak256,
+	}
+
+	impl HashAlgorithm {
+		//用这个算法计算数据的32字节摘要
+		pub fn hash(&self, data: &[u8]) -> [u8; 32] {
+			match self {
+				HashAlgorithm::Blake2_256 => sp_io::hashing::blake2_256(data),
+				HashAlgorithm::Sha2_256 => sp_io::hashing::sha2_256(data),
+				HashAlgorithm::Keccak256 => sp_io::hashing::keccak_256(data),
+			}
+		}
+	}
+
+	//存证：内容的32字节摘要和计算它的哈希算法，链上只保存摘要，不保存内容本身
+	#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct Claim {
+		pub algorithm: HashAlgorithm,
+		pub digest: [u8; 32],
+	}
+
+	impl Claim {
+		//用指定的哈希算法计算内容的摘要
+		pub fn from_content(algorithm: HashAlgorithm, content: &[u8]) -> Self {
+			Claim { algorithm, digest: algorithm.hash(content) }
+		}
+	}
+
+	//存证的描述、内容类型(MIME)和外部地址，押金由设置元数据的拥有者按字节数锁定
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(DescriptionLimit, ContentTypeLimit, UriLimit))]
+	#[codec(mel_bound(Balance: MaxEncodedLen))]
+	pub struct ClaimMetadata<
+		Balance,
+		DescriptionLimit: Get<u32>,
+		ContentTypeLimit: Get<u32>,
+		UriLimit: Get<u32>,
+	> {
+		pub description: BoundedVec<u8, DescriptionLimit>,
+		pub content_type: BoundedVec<u8, ContentTypeLimit>,
+		pub uri: BoundedVec<u8, UriLimit>,
+		pub deposit: Balance,
+	}
+
+	pub type MetadataOf<T> = ClaimMetadata<
+		BalanceOf<T>,
+		<T as Config>::MaxDescriptionLength,
+		<T as Config>::MaxContentTypeLength,
+		<T as Config>::MaxUriLength,
+	>;
+
+	//当前的存储版本，版本1的存证由任意字节改为带哈希算法的摘要
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
 	//定义pallet的配置接口(trait),要求继承系统配置,这样就能够继承到一些类型，如 block number，hash，account id
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		//添加关联类型
-		#[pallet::constant]
-		type MaxClaimLength: Get<u32>; //使用接口获得的
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		type WeightInfo: WeightInfo;
+
+		//存证的押金从这里锁定
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		//每个存证的基础押金
+		#[pallet::constant]
+		type ClaimDepositBase: Get<BalanceOf<Self>>;
+
+		//存证编码后每个字节的押金
+		#[pallet::constant]
+		type ClaimDepositPerByte: Get<BalanceOf<Self>>;
+
+		//存证描述的最大字节数
+		#[pallet::constant]
+		type MaxDescriptionLength: Get<u32>;
+
+		//存证内容类型的最大字节数
+		#[pallet::constant]
+		type MaxContentTypeLength: Get<u32>;
+
+		//存证外部地址的最大字节数
+		#[pallet::constant]
+		type MaxUriLength: Get<u32>;
+
+		//元数据每个字节需要锁定的押金
+		#[pallet::constant]
+		type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+
+		//批量调用一次最多处理的存证数量
+		#[pallet::constant]
+		type MaxClaimsPerBatch: Get<u32>;
 	}
 
 	//定义模块所需要的结构体
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)] //生成包含所有存储项的接口
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
-	//定义存储
+	//定义存储，以存证的摘要为键
 	#[pallet::storage]
-	pub type Proofs<T: Config> = StorageMap<
-		_,
-		Blake2_128Concat,
-		BoundedVec<u8, T::MaxClaimLength>,
-		(T::AccountId, T::BlockNumber),
-	>;
+	pub type Proofs<T: Config> =
+		StorageMap<_, Blake2_128Concat, Claim, (T::AccountId, T::BlockNumber)>;
+
+	//定义存储，每个存证锁定的押金，撤销时按这里记录的数额释放
+	//没有押金记录的存证（加入押金之前创建的）押金为0
+	#[pallet::storage]
+	#[pallet::getter(fn deposits)]
+	pub type Deposits<T: Config> = StorageMap<_, Blake2_128Concat, Claim, BalanceOf<T>, ValueQuery>;
+
+	//定义存储，存证的元数据，和存证分开保存，随存证一起转移和撤销
+	#[pallet::storage]
+	#[pallet::getter(fn claim_metadata)]
+	pub type ClaimMetadataOf<T: Config> = StorageMap<_, Blake2_128Concat, Claim, MetadataOf<T>>;
+
+	//定义存储，作为 Merkle 根上链的存证和树的叶子数量，撤销存证时一起删除
+	#[pallet::storage]
+	#[pallet::getter(fn anchored_roots)]
+	pub type AnchoredRoots<T: Config> = StorageMap<_, Blake2_128Concat, Claim, u32>;
 
 	//定义事件
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		ClaimCreated(T::AccountId, Vec<u8>),
-		ClaimRevoked(T::AccountId, Vec<u8>),
-		ClaimChanged(T::AccountId, Vec<u8>),
+		ClaimCreated(T::AccountId, Claim),
+		ClaimRevoked(T::AccountId, Claim),
+		ClaimChanged(T::AccountId, Claim),
+		//拥有者, 存证, 描述, 内容类型, 外部地址
+		ClaimMetadataSet(T::AccountId, Claim, Vec<u8>, Vec<u8>, Vec<u8>),
+		//拥有者, 根存证, 叶子数量
+		RootAnchored(T::AccountId, Claim, u32),
 	}
 
 	//定义错误
 	#[pallet::error]
 	pub enum Error<T> {
 		ProofAlreadyExist,
-		ClaimTooLong,
 		ClaimNotExist,
 		NotClaimOwner,
+		NotEnoughBalance,
+		DescriptionTooLong,
+		ContentTypeTooLong,
+		UriTooLong,
+		EmptyTree,
+		RecipientNotExist,
 	}
 
 	//定义hook
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::Migration::<T>::on_runtime_upgrade()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			crate::migrations::Migration::<T>::pre_upgrade()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			crate::migrations::Migration::<T>::post_upgrade()
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		//新建存储
-		#[pallet::weight(T::WeightInfo::create_claim(claim.len() as u32))]
-		//这里claim的类型是Vec<8>,它通常是一个代表具体内容的哈希值，因为链上存储十分宝贵，所以一般村放哈希值
-		pub fn create_claim(origin: OriginFor<T>, claim: Vec<u8>) -> DispatchResultWithPostInfo {
+		#[pallet::weight(T::WeightInfo::create_claim())]
+		//claim是内容的摘要，因为链上存储十分宝贵，所以只存放哈希值，可以通过runtime api的 create_claim_from_content 计算
+		pub fn create_claim(origin: OriginFor<T>, claim: Claim) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
 
-			//判断是否超出了字长
-			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
-				.map_err(|_| Error::<T>::ClaimTooLong)?;
+			Self::do_create_claim(&sender, claim)?;
 
-			//判断是否已经存储
-			ensure!(!Proofs::<T>::contains_key(&bounded_claim), Error::<T>::ProofAlreadyExist);
+			Ok(().into())
+		}
 
-			//执行存储
-			Proofs::<T>::insert(
-				bounded_claim,
-				(sender.clone(), frame_system::Pallet::<T>::block_number()),
-			);
+		//撤销存证
+		#[pallet::weight(T::WeightInfo::revoke_claim())]
+		pub fn revoke_claim(origin: OriginFor<T>, claim: Claim) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
 
-			Self::deposit_event(Event::ClaimCreated(sender, claim));
+			Self::do_revoke_claim(&sender, claim)?;
 
 			Ok(().into())
 		}
 
-		//撤销存证
-		#[pallet::weight(T::WeightInfo::revoke_claim(claim.len() as u32))]
-		pub fn revoke_claim(origin: OriginFor<T>, claim: Vec<u8>) -> DispatchResultWithPostInfo {
+		//转移存证，押金仍然锁定在新的拥有者名下，所以有押金的存证只能转给已经存在的账户
+		#[pallet::weight(T::WeightInfo::transfer_claim())]
+		#[transactional]
+		pub fn transfer_claim(
+			origin: OriginFor<T>,
+			claim: Claim,
+			dest: T::AccountId,
+		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
 
-			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
-				.map_err(|_| Error::<T>::ClaimTooLong)?;
+			Self::do_transfer_claim(&sender, claim, &dest)?;
+
+			Ok(().into())
+		}
 
-			let (owner, _) = Proofs::<T>::get(&bounded_claim).ok_or(Error::<T>::ClaimNotExist)?;
+		//把有 leaf_count 个叶子的 Merkle 树的根作为存证上链，押金和普通存证相同
+		//之后可以通过runtime api的 verify_inclusion 验证某个叶子是否在树中
+		#[pallet::weight(T::WeightInfo::anchor_root())]
+		pub fn anchor_root(
+			origin: OriginFor<T>,
+			root: Claim,
+			leaf_count: u32,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
 
-			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(leaf_count > 0, Error::<T>::EmptyTree);
 
-			Proofs::<T>::remove(&bounded_claim);
+			Self::do_create_claim(&sender, root)?;
+			AnchoredRoots::<T>::insert(&root, leaf_count);
 
-			Self::deposit_event(Event::ClaimRevoked(sender, claim));
+			Self::deposit_event(Event::RootAnchored(sender, root, leaf_count));
 
 			Ok(().into())
 		}
 
-		//转移存证
-		#[pallet::weight(T::WeightInfo::transfer_claim(claim.len() as u32))]
-		pub fn transfer_claim(
+		//批量新建存证，任何一个失败时全部回滚
+		#[pallet::weight(T::WeightInfo::create_claims(claims.len() as u32))]
+		#[transactional]
+		pub fn create_claims(
 			origin: OriginFor<T>,
-			claim: Vec<u8>,
+			claims: BoundedVec<Claim, T::MaxClaimsPerBatch>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			for claim in claims {
+				Self::do_create_claim(&sender, claim)?;
+			}
+
+			Ok(().into())
+		}
+
+		//批量撤销存证，任何一个失败时全部回滚
+		//预先按每个存证都有最长元数据收取权重，执行后按实际的元数据字节数退还
+		#[pallet::weight(T::WeightInfo::revoke_claims(
+			claims.len() as u32,
+			Pallet::<T>::max_metadata_bytes().saturating_mul(claims.len() as u32)
+		))]
+		#[transactional]
+		pub fn revoke_claims(
+			origin: OriginFor<T>,
+			claims: BoundedVec<Claim, T::MaxClaimsPerBatch>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let count = claims.len() as u32;
+			let mut metadata_bytes = 0u32;
+			for claim in claims {
+				metadata_bytes =
+					metadata_bytes.saturating_add(Self::do_revoke_claim(&sender, claim)?);
+			}
+
+			Ok(Some(T::WeightInfo::revoke_claims(count, metadata_bytes)).into())
+		}
+
+		//把多个存证转给同一个账户，任何一个失败时全部回滚
+		//预先按每个存证都有最长元数据收取权重，执行后按实际的元数据字节数退还
+		#[pallet::weight(T::WeightInfo::transfer_claims(
+			claims.len() as u32,
+			Pallet::<T>::max_metadata_bytes().saturating_mul(claims.len() as u32)
+		))]
+		#[transactional]
+		pub fn transfer_claims(
+			origin: OriginFor<T>,
+			claims: BoundedVec<Claim, T::MaxClaimsPerBatch>,
 			dest: T::AccountId,
 		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
 
-			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
-				.map_err(|_| Error::<T>::ClaimTooLong)?;
+			let count = claims.len() as u32;
+			let mut metadata_bytes = 0u32;
+			for claim in claims {
+				metadata_bytes =
+					metadata_bytes.saturating_add(Self::do_transfer_claim(&sender, claim, &dest)?);
+			}
 
-			let (owner, _block_number) =
-				Proofs::<T>::get(&bounded_claim).ok_or(Error::<T>::ClaimNotExist)?;
+			Ok(Some(T::WeightInfo::transfer_claims(count, metadata_bytes)).into())
+		}
+
+		//设置存证的描述、内容类型和外部地址，按字节数锁定押金，多退少补
+		#[pallet::weight(T::WeightInfo::set_claim_metadata(
+			(description.len() + content_type.len() + uri.len()) as u32
+		))]
+		pub fn set_claim_metadata(
+			origin: OriginFor<T>,
+			claim: Claim,
+			description: Vec<u8>,
+			content_type: Vec<u8>,
+			uri: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let (owner, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
 
 			ensure!(owner == sender, Error::<T>::NotClaimOwner);
 
-			Proofs::<T>::insert(&bounded_claim, (dest, frame_system::Pallet::<T>::block_number()));
-			//Proofs::<T>::mutate(&bounded_claim, |v| *v = Some((dest, _block_number)));
+			let bounded_description: BoundedVec<u8, T::MaxDescriptionLength> =
+				description.clone().try_into().map_err(|_| Error::<T>::DescriptionTooLong)?;
+			let bounded_content_type: BoundedVec<u8, T::MaxContentTypeLength> =
+				content_type.clone().try_into().map_err(|_| Error::<T>::ContentTypeTooLong)?;
+			let bounded_uri: BoundedVec<u8, T::MaxUriLength> =
+				uri.clone().try_into().map_err(|_| Error::<T>::UriTooLong)?;
+
+			let bytes =
+				(bounded_description.len() + bounded_content_type.len() + bounded_uri.len()) as u32;
+			let deposit = Self::metadata_deposit(bytes);
+			let old_deposit = Self::claim_metadata(&claim)
+				.map(|metadata| metadata.deposit)
+				.unwrap_or_else(Zero::zero);
+
+			//元数据押金随存证转移，所以之前的押金一定由当前拥有者锁定
+			if deposit > old_deposit {
+				T::Currency::reserve(&sender, deposit - old_deposit)
+					.map_err(|_| Error::<T>::NotEnoughBalance)?;
+			} else {
+				T::Currency::unreserve(&sender, old_deposit - deposit);
+			}
+
+			ClaimMetadataOf::<T>::insert(
+				&claim,
+				ClaimMetadata {
+					description: bounded_description,
+					content_type: bounded_content_type,
+					uri: bounded_uri,
+					deposit,
+				},
+			);
 
-			Self::deposit_event(Event::ClaimChanged(sender, claim));
+			Self::deposit_event(Event::ClaimMetadataSet(
+				sender,
+				claim,
+				description,
+				content_type,
+				uri,
+			));
 
 			Ok(().into())
 		}
 	}
+
+	impl<T: Config> Pallet<T> {
+		//存证的押金 = 基础押金 + 每字节押金 * 存证编码后的字节数
+		pub fn claim_deposit() -> BalanceOf<T> {
+			T::ClaimDepositPerByte::get()
+				.saturating_mul((Claim::max_encoded_len() as u32).into())
+				.saturating_add(T::ClaimDepositBase::get())
+		}
+
+		//元数据的押金 = 每字节押金 * 字节数
+		pub fn metadata_deposit(bytes: u32) -> BalanceOf<T> {
+			T::MetadataDepositPerByte::get().saturating_mul(bytes.into())
+		}
+
+		//一个存证的元数据最多的字节数
+		pub fn max_metadata_bytes() -> u32 {
+			T::MaxDescriptionLength::get()
+				.saturating_add(T::MaxContentTypeLength::get())
+				.saturating_add(T::MaxUriLength::get())
+		}
+
+		//元数据的字节数，和计算押金时相同
+		fn metadata_bytes(metadata: &MetadataOf<T>) -> u32 {
+			(metadata.description.len() + metadata.content_type.len() + metadata.uri.len()) as u32
+		}
+
+		fn do_create_claim(sender: &T::AccountId, claim: Claim) -> DispatchResult {
+			//判断是否已经存储
+			ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+
+			//锁定押金
+			let deposit = Self::claim_deposit();
+			T::Currency::reserve(sender, deposit).map_err(|_| Error::<T>::NotEnoughBalance)?;
+
+			//执行存储
+			Deposits::<T>::insert(&claim, deposit);
+			Proofs::<T>::insert(
+				&claim,
+				(sender.clone(), frame_system::Pallet::<T>::block_number()),
+			);
+
+			Self::deposit_event(Event::ClaimCreated(sender.clone(), claim));
+
+			Ok(())
+		}
+
+		//返回存证的元数据字节数，用来计算批量调用的实际权重
+		fn do_revoke_claim(sender: &T::AccountId, claim: Claim) -> Result<u32, DispatchError> {
+			let (owner, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			ensure!(&owner == sender, Error::<T>::NotClaimOwner);
+
+			//释放存证和元数据的押金
+			let deposit = Deposits::<T>::take(&claim);
+			T::Currency::unreserve(sender, deposit);
+			let mut metadata_bytes = 0;
+			if let Some(metadata) = ClaimMetadataOf::<T>::take(&claim) {
+				T::Currency::unreserve(sender, metadata.deposit);
+				metadata_bytes = Self::metadata_bytes(&metadata);
+			}
+
+			Proofs::<T>::remove(&claim);
+			AnchoredRoots::<T>::remove(&claim);
+
+			Self::deposit_event(Event::ClaimRevoked(sender.clone(), claim));
+
+			Ok(metadata_bytes)
+		}
+
+		//元数据押金转移失败时存证押金已经转移，调用者需要用 transactional 保证回滚
+		//返回存证的元数据字节数，用来计算批量调用的实际权重
+		fn do_transfer_claim(
+			sender: &T::AccountId,
+			claim: Claim,
+			dest: &T::AccountId,
+		) -> Result<u32, DispatchError> {
+			let (owner, _block_number) =
+				Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			ensure!(&owner == sender, Error::<T>::NotClaimOwner);
+
+			//押金和元数据押金随存证转给新的拥有者，记录实际转过去的数额
+			let deposit = Deposits::<T>::get(&claim);
+			if !deposit.is_zero() {
+				let moved = Self::repatriate_deposit(sender, dest, deposit)?;
+				Deposits::<T>::insert(&claim, moved);
+			}
+			let mut metadata_bytes = 0;
+			if let Some(mut metadata) = ClaimMetadataOf::<T>::get(&claim) {
+				metadata.deposit = Self::repatriate_deposit(sender, dest, metadata.deposit)?;
+				metadata_bytes = Self::metadata_bytes(&metadata);
+				ClaimMetadataOf::<T>::insert(&claim, metadata);
+			}
+
+			Proofs::<T>::insert(&claim, (dest.clone(), frame_system::Pallet::<T>::block_number()));
+			//Proofs::<T>::mutate(&claim, |v| *v = Some((dest, _block_number)));
+
+			Self::deposit_event(Event::ClaimChanged(sender.clone(), claim));
+
+			Ok(metadata_bytes)
+		}
+
+		//把锁定的押金转给新的拥有者，仍然保持锁定，返回实际转过去的数额
+		fn repatriate_deposit(
+			from: &T::AccountId,
+			to: &T::AccountId,
+			deposit: BalanceOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			//锁定余额不能转入没有余额的账户
+			ensure!(!T::Currency::total_balance(to).is_zero(), Error::<T>::RecipientNotExist);
+
+			let remaining =
+				T::Currency::repatriate_reserved(from, to, deposit, BalanceStatus::Reserved)?;
+			Ok(deposit.saturating_sub(remaining))
+		}
+
+		//用指定的哈希算法计算内容的存证，供runtime api使用
+		pub fn create_claim_from_content(algorithm: HashAlgorithm, content: Vec<u8>) -> Claim {
+			Claim::from_content(algorithm, &content)
+		}
+
+		//叶子是否包含在已经上链的 Merkle 根中，根不存在或没有通过 anchor_root 上链时返回false
+		pub fn verify_inclusion(root: Claim, leaf: [u8; 32], proof: MerkleProof) -> bool {
+			if !Proofs::<T>::contains_key(&root) {
+				return false
+			}
+
+			match Self::anchored_roots(&root) {
+				Some(leaf_count) => crate::merkle::verify(&root, &leaf, leaf_count, &proof),
+				None => false,
+			}
+		}
+	}
 }
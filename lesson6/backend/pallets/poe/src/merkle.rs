@@ -0,0 +1,82 @@
+//存证的 Merkle 树：用 anchor_root 把很多文档摘要的根作为一个存证上链，之后验证单个文档是否在树中
+//
+//树的规则，链下构建树的 pallet-poe-merkle 和链上的验证都使用这里的函数：
+//- 叶子是文档的32字节摘要，叶子哈希 = H(0x00 || 叶子)
+//- 中间节点哈希 = H(0x01 || 左 || 右)，叶子和中间节点加不同的前缀，避免把中间节点当作叶子
+//- 每一层从左到右两两合并，落单的最后一个节点直接升到上一层，不和自己合并
+//- H 是根存证的哈希算法
+
+use crate::{Claim, HashAlgorithm};
+use codec::{Decode, Encode};
+use frame_support::RuntimeDebug;
+use scale_info::TypeInfo;
+use sp_std::prelude::*;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+//叶子哈希和中间节点哈希的前缀
+pub const LEAF_PREFIX: u8 = 0;
+pub const NODE_PREFIX: u8 = 1;
+
+//叶子的包含证明：叶子的位置，以及从叶子到根路径上每一层的兄弟节点（落单升层的那一层没有兄弟节点）
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct MerkleProof {
+	pub leaf_index: u32,
+	pub siblings: Vec<[u8; 32]>,
+}
+
+pub fn hash_leaf(algorithm: HashAlgorithm, leaf: &[u8; 32]) -> [u8; 32] {
+	let mut data = [0u8; 33];
+	data[0] = LEAF_PREFIX;
+	data[1..].copy_from_slice(leaf);
+	algorithm.hash(&data)
+}
+
+pub fn hash_node(algorithm: HashAlgorithm, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+	let mut data = [0u8; 65];
+	data[0] = NODE_PREFIX;
+	data[1..33].copy_from_slice(left);
+	data[33..].copy_from_slice(right);
+	algorithm.hash(&data)
+}
+
+//按证明从叶子算出根，叶子位置超出范围或兄弟节点的数量和树的形状不符时返回None
+pub fn compute_root(
+	algorithm: HashAlgorithm,
+	leaf: &[u8; 32],
+	leaf_count: u32,
+	proof: &MerkleProof,
+) -> Option<[u8; 32]> {
+	if proof.leaf_index >= leaf_count {
+		return None
+	}
+
+	let mut hash = hash_leaf(algorithm, leaf);
+	let mut index = proof.leaf_index;
+	let mut width = leaf_count;
+	let mut siblings = proof.siblings.iter();
+
+	while width > 1 {
+		if index % 2 == 1 {
+			hash = hash_node(algorithm, siblings.next()?, &hash);
+		} else if index + 1 < width {
+			hash = hash_node(algorithm, &hash, siblings.next()?);
+		}
+
+		index /= 2;
+		width = width / 2 + width % 2;
+	}
+
+	if siblings.next().is_some() {
+		return None
+	}
+
+	Some(hash)
+}
+
+//叶子是否包含在以 root 为根、有 leaf_count 个叶子的树中
+pub fn verify(root: &Claim, leaf: &[u8; 32], leaf_count: u32, proof: &MerkleProof) -> bool {
+	compute_root(root.algorithm, leaf, leaf_count, proof) == Some(root.digest)
+}
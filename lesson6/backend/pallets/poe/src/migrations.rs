@@ -0,0 +1,181 @@
+//poe 存储迁移
+//
+//版本0：存证是任意长度的字节，Proofs 和 Deposits 以 BoundedVec<u8, MaxClaimLength> 为键
+//版本1：存证是带哈希算法的32字节摘要
+
+use crate::*;
+use core::marker::PhantomData;
+use frame_support::{
+	log,
+	pallet_prelude::*,
+	storage::migration::{storage_key_iter, take_storage_item},
+	traits::{
+		GetStorageVersion, OnRuntimeUpgrade, PalletInfoAccess, ReservableCurrency, StorageVersion,
+	},
+	weights::Weight,
+};
+use sp_std::prelude::*;
+
+#[cfg(feature = "try-runtime")]
+use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+#[cfg(feature = "try-runtime")]
+use frame_support::sp_runtime::traits::{Saturating, Zero};
+
+//按链上存储版本依次执行迁移，已经执行过的迁移会被跳过
+pub struct Migration<T>(PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+	fn on_runtime_upgrade() -> Weight {
+		v1::migrate::<T>()
+	}
+
+	//记录升级后应有的存证数量和锁定的押金总额
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		let on_chain_version = Pallet::<T>::on_chain_storage_version();
+
+		let (count, total_deposit) = if on_chain_version < 1 {
+			v1::expected_claims::<T>()
+		} else {
+			let count = Proofs::<T>::iter_keys().count() as u32;
+			let total_deposit = Deposits::<T>::iter_values()
+				.fold(BalanceOf::<T>::zero(), |total, deposit| total.saturating_add(deposit));
+			(count, total_deposit)
+		};
+
+		Self::set_temp_storage(count, "claim_count");
+		Self::set_temp_storage(total_deposit, "total_deposit");
+
+		log::info!("pre-upgrade: {} claims at storage version {:?}", count, on_chain_version);
+
+		Ok(())
+	}
+
+	//升级后版本为最新，存证数量和押金总额不变，每个押金都属于一个存证
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		ensure!(
+			Pallet::<T>::on_chain_storage_version() == Pallet::<T>::current_storage_version(),
+			"storage version was not updated"
+		);
+
+		let count: u32 = Self::get_temp_storage("claim_count").ok_or("missing claim count")?;
+		let total_deposit: BalanceOf<T> =
+			Self::get_temp_storage("total_deposit").ok_or("missing total deposit")?;
+
+		ensure!(Proofs::<T>::iter_keys().count() as u32 == count, "claim count changed");
+
+		let mut migrated_deposit = BalanceOf::<T>::zero();
+		for (claim, deposit) in Deposits::<T>::iter() {
+			ensure!(Proofs::<T>::contains_key(&claim), "deposit without claim");
+			migrated_deposit = migrated_deposit.saturating_add(deposit);
+		}
+		ensure!(migrated_deposit == total_deposit, "total deposit changed");
+
+		log::info!("post-upgrade: {} claims checked", count);
+
+		Ok(())
+	}
+}
+
+//v0 -> v1：存证的键从任意字节改为 Claim
+pub mod v1 {
+	use super::*;
+
+	//v0 版本的存储项名，和 v1 相同，但键的编码不同
+	pub const OLD_PROOFS: &[u8] = b"Proofs";
+	pub const OLD_DEPOSITS: &[u8] = b"Deposits";
+
+	//substrate前端模板用 blake2_256 计算存证，32字节的旧存证视为 Blake2-256 摘要，
+	//其它长度的旧存证是原始内容，用 Blake2-256 计算摘要
+	pub fn new_claim(old_claim: &[u8]) -> Claim {
+		match <[u8; 32]>::try_from(old_claim) {
+			Ok(digest) => Claim { algorithm: HashAlgorithm::Blake2_256, digest },
+			Err(_) => Claim::from_content(HashAlgorithm::Blake2_256, old_claim),
+		}
+	}
+
+	//按迁移的顺序计算迁移后的存证数量和押金总额
+	#[cfg(feature = "try-runtime")]
+	pub fn expected_claims<T: Config>() -> (u32, BalanceOf<T>) {
+		use sp_std::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
+
+		let pallet_name = <Pallet<T> as PalletInfoAccess>::name();
+		let old_deposits: BTreeMap<Vec<u8>, BalanceOf<T>> =
+			storage_key_iter::<Vec<u8>, BalanceOf<T>, Blake2_128Concat>(
+				pallet_name.as_bytes(),
+				OLD_DEPOSITS,
+			)
+			.collect();
+
+		let mut claims = BTreeSet::new();
+		let mut total_deposit = BalanceOf::<T>::zero();
+		for (old_claim, _) in storage_key_iter::<
+			Vec<u8>,
+			(T::AccountId, T::BlockNumber),
+			Blake2_128Concat,
+		>(pallet_name.as_bytes(), OLD_PROOFS)
+		{
+			if claims.insert(new_claim(&old_claim).encode()) {
+				let deposit = old_deposits.get(&old_claim).copied().unwrap_or_default();
+				total_deposit = total_deposit.saturating_add(deposit);
+			}
+		}
+
+		(claims.len() as u32, total_deposit)
+	}
+
+	pub fn migrate<T: Config>() -> Weight {
+		let on_chain_version = Pallet::<T>::on_chain_storage_version();
+
+		if on_chain_version >= 1 {
+			log::info!("poe storage is already at {:?}, skip migration", on_chain_version);
+			return T::DbWeight::get().reads(1)
+		}
+
+		//BoundedVec 和 Vec 的编码相同，先取出全部旧存证再按新的键写回
+		let pallet_name = <Pallet<T> as PalletInfoAccess>::name();
+		let old_proofs: Vec<(Vec<u8>, (T::AccountId, T::BlockNumber))> =
+			storage_key_iter::<Vec<u8>, (T::AccountId, T::BlockNumber), Blake2_128Concat>(
+				pallet_name.as_bytes(),
+				OLD_PROOFS,
+			)
+			.drain()
+			.collect();
+
+		//拥有者、区块和押金保持不变
+		//同一内容既存了摘要又存了原文时，只保留先迁移的存证，另一个的押金释放
+		let mut migrated = 0u64;
+		let mut merged = 0u64;
+		for (old_claim, (owner, block_number)) in old_proofs {
+			let deposit = take_storage_item::<Vec<u8>, BalanceOf<T>, Blake2_128Concat>(
+				pallet_name.as_bytes(),
+				OLD_DEPOSITS,
+				&old_claim,
+			)
+			.unwrap_or_default();
+
+			let claim = new_claim(&old_claim);
+			if Proofs::<T>::contains_key(&claim) {
+				T::Currency::unreserve(&owner, deposit);
+				merged += 1;
+				continue
+			}
+
+			Deposits::<T>::insert(&claim, deposit);
+			Proofs::<T>::insert(&claim, (owner, block_number));
+			migrated += 1;
+		}
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+
+		log::info!(
+			"migrated {} claims to storage version 1, merged {} duplicate claims",
+			migrated,
+			merged
+		);
+
+		let total = migrated + merged;
+		T::DbWeight::get().reads_writes(total * 3 + 1, total * 4 + 1)
+	}
+}
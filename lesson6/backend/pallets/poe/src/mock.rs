@@ -18,6 +18,8 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		//存证的押金使用 Balances 模块
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		//实现所定义的pallet
 		PoeModule: pallet_poe::{Pallet, Call, Storage, Event<T>},
 	}
@@ -41,7 +43,7 @@ impl system::Config for Test {
 	type BlockHashCount = ConstU64<250>;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
@@ -50,13 +52,37 @@ impl system::Config for Test {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
 impl pallet_poe::Config for Test {
-	type MaxClaimLength = ConstU32<512>;
 	type Event = Event;
 	type WeightInfo = ();
+	type Currency = Balances;
+	type ClaimDepositBase = ConstU64<10>;
+	type ClaimDepositPerByte = ConstU64<1>;
+	type MaxDescriptionLength = ConstU32<32>;
+	type MaxContentTypeLength = ConstU32<16>;
+	type MaxUriLength = ConstU32<32>;
+	type MetadataDepositPerByte = ConstU64<1>;
+	type MaxClaimsPerBatch = ConstU32<4>;
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	//账户1和2有余额，账户3只够存在
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(1, 100), (2, 100), (3, 1)] }
+		.assimilate_storage(&mut storage)
+		.unwrap();
+	storage.into()
 }
@@ -1,6 +1,11 @@
 use super::*;
 use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok, BoundedVec};
+use frame_support::{assert_noop, assert_ok};
+
+//用 Blake2-256 计算内容的存证
+fn claim_of(content: &[u8]) -> Claim {
+	Claim::from_content(HashAlgorithm::Blake2_256, content)
+}
 
 //1.测试创建存证函数
 //1.1 通过测试
@@ -10,16 +15,13 @@ use frame_support::{assert_noop, assert_ok, BoundedVec};
 fn create_claim_works() {
 	new_test_ext().execute_with(|| {
 		//定义测试输入
-		let claim = vec![1, 2];
+		let claim = claim_of(&[1, 2]);
 		//断言测试能够成功执行
-		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim));
 
-		//转换输入
-		let bounded_claim =
-			BoundedVec::<u8, <Test as Config>::MaxClaimLength>::try_from(claim.clone()).unwrap();
 		//断言存储结果
 		assert_eq!(
-			Proofs::<Test>::get(&bounded_claim),
+			Proofs::<Test>::get(&claim),
 			Some((1, frame_system::Pallet::<Test>::block_number()))
 		);
 	})
@@ -29,27 +31,39 @@ fn create_claim_works() {
 fn create_claim_failed_when_claim_already_exist() {
 	new_test_ext().execute_with(|| {
 		//构造输入
-		let claim: Vec<u8> = vec![0, 1];
+		let claim = claim_of(&[0, 1]);
 		//执行存储
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		let _ = PoeModule::create_claim(Origin::signed(1), claim);
 		//判断存储已经存在
 		assert_noop!(
-			PoeModule::create_claim(Origin::signed(1), claim.clone()),
+			PoeModule::create_claim(Origin::signed(1), claim),
 			Error::<Test>::ProofAlreadyExist
 		);
 	})
 }
 
 #[test]
-fn create_claim_failed_when_claim_too_long() {
+fn claim_from_content_uses_algorithm() {
 	new_test_ext().execute_with(|| {
-		//构造输入
-		let claim = vec![0, 255];
-		//
-		assert_noop!(
-			PoeModule::create_claim(Origin::signed(1), claim.clone()),
-			Error::<Test>::ClaimTooLong
+		//空内容的 SHA-256 和 Keccak-256 摘要是公开的测试向量
+		let sha2 = PoeModule::create_claim_from_content(HashAlgorithm::Sha2_256, vec![]);
+		assert_eq!(sha2.algorithm, HashAlgorithm::Sha2_256);
+		assert_eq!(
+			sha2.digest,
+			hex_literal::hex!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
 		);
+
+		let keccak = PoeModule::create_claim_from_content(HashAlgorithm::Keccak256, vec![]);
+		assert_eq!(
+			keccak.digest,
+			hex_literal::hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+		);
+
+		//同一份内容用不同算法得到不同的存证
+		let content = b"hello poe".to_vec();
+		let blake2 = PoeModule::create_claim_from_content(HashAlgorithm::Blake2_256, content);
+		assert_eq!(blake2, claim_of(b"hello poe"));
+		assert_ne!(blake2, Claim::from_content(HashAlgorithm::Sha2_256, b"hello poe"));
 	})
 }
 
@@ -57,13 +71,13 @@ fn create_claim_failed_when_claim_too_long() {
 fn revoke_claim_failed_when_claim_not_exist() {
 	new_test_ext().execute_with(|| {
 		//构造输入参数
-		let claim: Vec<u8> = vec![0, 1];
+		let claim = claim_of(&[0, 1]);
 
 		//不执行存储
 
 		//查看是否能删除
 		assert_noop!(
-			PoeModule::revoke_claim(Origin::signed(1), claim.clone()),
+			PoeModule::revoke_claim(Origin::signed(1), claim),
 			Error::<Test>::ClaimNotExist
 		);
 	})
@@ -73,21 +87,17 @@ fn revoke_claim_failed_when_claim_not_exist() {
 fn transfer_claim_failed_when_claim_not_exist() {
 	new_test_ext().execute_with(|| {
 		//构造输入参数
-		let claim: Vec<u8> = vec![0, 1];
+		let claim = claim_of(&[0, 1]);
 		let dest: u64 = 2;
 
 		//创建存储
-		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim));
 		//转移存储
-		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim.clone(), dest));
-
-		//转换输入
-		let bounded_claim =
-			BoundedVec::<u8, <Test as Config>::MaxClaimLength>::try_from(claim.clone()).unwrap();
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, dest));
 
 		//查看存储结果，是否被转移
 		assert_eq!(
-			Proofs::<Test>::get(&bounded_claim),
+			Proofs::<Test>::get(&claim),
 			Some((2, frame_system::Pallet::<Test>::block_number()))
 		);
 	})
@@ -97,15 +107,454 @@ fn transfer_claim_failed_when_claim_not_exist() {
 fn transfer_claim_works() {
 	new_test_ext().execute_with(|| {
 		//构造输入参数
-		let claim: Vec<u8> = vec![0, 1];
+		let claim = claim_of(&[0, 1]);
 		let dest: u64 = 2;
 
 		//不执行任何操作
 
 		//查看转移报错
 		assert_noop!(
-			PoeModule::transfer_claim(Origin::signed(1), claim.clone(), dest),
+			PoeModule::transfer_claim(Origin::signed(1), claim, dest),
 			Error::<Test>::ClaimNotExist
 		);
 	})
 }
+
+//2.测试存证押金
+
+#[test]
+fn create_claim_reserves_deposit() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim));
+
+		//押金 = 基础押金10 + 每字节1 * 33（1字节算法 + 32字节摘要）
+		assert_eq!(PoeModule::deposits(&claim), 43);
+		assert_eq!(Balances::reserved_balance(&1), 43);
+		assert_eq!(PoeModule::claim_deposit(), 43);
+	})
+}
+
+#[test]
+fn create_claim_failed_when_not_enough_balance() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		//账户3的余额不够锁定押金
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(3), claim),
+			Error::<Test>::NotEnoughBalance
+		);
+	})
+}
+
+#[test]
+fn revoke_claim_unreserves_deposit() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim));
+
+		//只有拥有者可以撤销
+		assert_noop!(
+			PoeModule::revoke_claim(Origin::signed(2), claim),
+			Error::<Test>::NotClaimOwner
+		);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
+
+		assert_eq!(Proofs::<Test>::get(&claim), None);
+		assert_eq!(PoeModule::deposits(&claim), 0);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 100);
+	})
+}
+
+#[test]
+fn transfer_claim_moves_deposit() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim));
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, 2));
+
+		//押金从原拥有者转到新的拥有者，仍然是锁定状态
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 57);
+		assert_eq!(Balances::reserved_balance(&2), 43);
+
+		//新的拥有者撤销时释放押金
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(2), claim));
+		assert_eq!(Balances::reserved_balance(&2), 0);
+		assert_eq!(Balances::free_balance(&2), 143);
+	})
+}
+
+#[test]
+fn transfer_claim_failed_when_recipient_not_exist() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim));
+
+		//账户4没有余额，无法接收锁定的押金
+		assert_noop!(
+			PoeModule::transfer_claim(Origin::signed(1), claim, 4),
+			Error::<Test>::RecipientNotExist
+		);
+		assert_noop!(
+			PoeModule::transfer_claims(Origin::signed(1), batch(vec![claim]), 4),
+			Error::<Test>::RecipientNotExist
+		);
+
+		//账户有余额后可以转移
+		assert_ok!(Balances::set_balance(Origin::root(), 4, 1, 0));
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, 4));
+		assert_eq!(Balances::reserved_balance(&4), 43);
+	})
+}
+
+//3.测试存证元数据
+
+#[test]
+fn set_claim_metadata_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let claim = claim_of(&[0, 1]);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim));
+
+		//元数据押金 = 每字节1 * (3 + 10 + 8)
+		assert_ok!(PoeModule::set_claim_metadata(
+			Origin::signed(1),
+			claim,
+			b"doc".to_vec(),
+			b"text/plain".to_vec(),
+			b"ipfs://x".to_vec()
+		));
+		System::assert_last_event(
+			crate::Event::ClaimMetadataSet(
+				1,
+				claim,
+				b"doc".to_vec(),
+				b"text/plain".to_vec(),
+				b"ipfs://x".to_vec(),
+			)
+			.into(),
+		);
+
+		let metadata = PoeModule::claim_metadata(&claim).unwrap();
+		assert_eq!(metadata.description.to_vec(), b"doc".to_vec());
+		assert_eq!(metadata.content_type.to_vec(), b"text/plain".to_vec());
+		assert_eq!(metadata.uri.to_vec(), b"ipfs://x".to_vec());
+		assert_eq!(metadata.deposit, 21);
+		assert_eq!(Balances::reserved_balance(&1), 43 + 21);
+
+		//改为更短的元数据时退还多余的押金
+		assert_ok!(PoeModule::set_claim_metadata(
+			Origin::signed(1),
+			claim,
+			vec![],
+			b"text/plain".to_vec(),
+			vec![]
+		));
+		assert_eq!(PoeModule::claim_metadata(&claim).unwrap().deposit, 10);
+		assert_eq!(Balances::reserved_balance(&1), 43 + 10);
+	})
+}
+
+#[test]
+fn set_claim_metadata_failed_when_invalid() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+
+		assert_noop!(
+			PoeModule::set_claim_metadata(Origin::signed(1), claim, vec![], vec![], vec![]),
+			Error::<Test>::ClaimNotExist
+		);
+
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim));
+
+		assert_noop!(
+			PoeModule::set_claim_metadata(Origin::signed(2), claim, vec![], vec![], vec![]),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_noop!(
+			PoeModule::set_claim_metadata(Origin::signed(1), claim, vec![0; 33], vec![], vec![]),
+			Error::<Test>::DescriptionTooLong
+		);
+		assert_noop!(
+			PoeModule::set_claim_metadata(Origin::signed(1), claim, vec![], vec![0; 17], vec![]),
+			Error::<Test>::ContentTypeTooLong
+		);
+		assert_noop!(
+			PoeModule::set_claim_metadata(Origin::signed(1), claim, vec![], vec![], vec![0; 33]),
+			Error::<Test>::UriTooLong
+		);
+
+		//余额不够锁定元数据押金：账户1剩余57
+		assert_noop!(
+			PoeModule::set_claim_metadata(
+				Origin::signed(1),
+				claim,
+				vec![0; 32],
+				vec![0; 16],
+				vec![0; 32]
+			),
+			Error::<Test>::NotEnoughBalance
+		);
+	})
+}
+
+#[test]
+fn transfer_and_revoke_claim_move_metadata_deposit() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim));
+		assert_ok!(PoeModule::set_claim_metadata(
+			Origin::signed(1),
+			claim,
+			b"doc".to_vec(),
+			vec![],
+			vec![]
+		));
+
+		//元数据和押金随存证转给新的拥有者
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, 2));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::reserved_balance(&2), 43 + 3);
+		assert_eq!(PoeModule::claim_metadata(&claim).unwrap().deposit, 3);
+
+		//撤销时删除元数据并释放押金
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(2), claim));
+		assert!(PoeModule::claim_metadata(&claim).is_none());
+		assert_eq!(Balances::reserved_balance(&2), 0);
+		assert_eq!(Balances::free_balance(&2), 100 + 43 + 3);
+	})
+}
+
+//4.测试批量调用
+
+fn batch(claims: Vec<Claim>) -> BoundedVec<Claim, <Test as Config>::MaxClaimsPerBatch> {
+	claims.try_into().unwrap()
+}
+
+#[test]
+fn create_claims_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Balances::set_balance(Origin::root(), 1, 1_000, 0));
+
+		let claims = vec![claim_of(&[0]), claim_of(&[1]), claim_of(&[2])];
+		assert_ok!(PoeModule::create_claims(Origin::signed(1), batch(claims.clone())));
+
+		//每个存证都有自己的事件和押金
+		for claim in claims.iter() {
+			assert_eq!(Proofs::<Test>::get(claim), Some((1, 1)));
+			assert_eq!(PoeModule::deposits(claim), 43);
+			System::assert_has_event(crate::Event::ClaimCreated(1, *claim).into());
+		}
+		assert_eq!(Balances::reserved_balance(&1), 43 * 3);
+	})
+}
+
+#[test]
+fn create_claims_is_all_or_nothing() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Balances::set_balance(Origin::root(), 1, 1_000, 0));
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim_of(&[2])));
+
+		//最后一个存证已经存在，前面的存证也不会创建
+		assert_noop!(
+			PoeModule::create_claims(
+				Origin::signed(1),
+				batch(vec![claim_of(&[0]), claim_of(&[1]), claim_of(&[2])])
+			),
+			Error::<Test>::ProofAlreadyExist
+		);
+
+		//同一批中重复的存证
+		assert_noop!(
+			PoeModule::create_claims(
+				Origin::signed(1),
+				batch(vec![claim_of(&[0]), claim_of(&[0])])
+			),
+			Error::<Test>::ProofAlreadyExist
+		);
+
+		//余额只够锁定一部分押金：账户2的100只够两个
+		assert_noop!(
+			PoeModule::create_claims(
+				Origin::signed(2),
+				batch(vec![claim_of(&[3]), claim_of(&[4]), claim_of(&[5])])
+			),
+			Error::<Test>::NotEnoughBalance
+		);
+	})
+}
+
+#[test]
+fn revoke_claims_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Balances::set_balance(Origin::root(), 1, 1_000, 0));
+		let claims = vec![claim_of(&[0]), claim_of(&[1])];
+		assert_ok!(PoeModule::create_claims(Origin::signed(1), batch(claims.clone())));
+		assert_ok!(PoeModule::set_claim_metadata(
+			Origin::signed(1),
+			claims[1],
+			b"doc".to_vec(),
+			vec![],
+			vec![]
+		));
+		assert_ok!(PoeModule::create_claim(Origin::signed(2), claim_of(&[2])));
+
+		//其中一个存证不属于调用者时全部回滚
+		assert_noop!(
+			PoeModule::revoke_claims(
+				Origin::signed(1),
+				batch(vec![claims[0], claims[1], claim_of(&[2])])
+			),
+			Error::<Test>::NotClaimOwner
+		);
+
+		//按实际的元数据字节数退还预先收取的权重
+		let post_info = PoeModule::revoke_claims(Origin::signed(1), batch(claims.clone())).unwrap();
+		assert_eq!(post_info.actual_weight, Some(<() as WeightInfo>::revoke_claims(2, 3)));
+		for claim in claims.iter() {
+			assert_eq!(Proofs::<Test>::get(claim), None);
+		}
+		assert!(PoeModule::claim_metadata(&claims[1]).is_none());
+		assert_eq!(Balances::reserved_balance(&1), 0);
+	})
+}
+
+#[test]
+fn transfer_claims_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Balances::set_balance(Origin::root(), 1, 1_000, 0));
+		let claims = vec![claim_of(&[0]), claim_of(&[1])];
+		assert_ok!(PoeModule::create_claims(Origin::signed(1), batch(claims.clone())));
+
+		//其中一个存证不存在时全部回滚
+		assert_noop!(
+			PoeModule::transfer_claims(
+				Origin::signed(1),
+				batch(vec![claims[0], claims[1], claim_of(&[2])]),
+				2
+			),
+			Error::<Test>::ClaimNotExist
+		);
+
+		let post_info =
+			PoeModule::transfer_claims(Origin::signed(1), batch(claims.clone()), 2).unwrap();
+		assert_eq!(post_info.actual_weight, Some(<() as WeightInfo>::transfer_claims(2, 0)));
+		for claim in claims.iter() {
+			assert_eq!(Proofs::<Test>::get(claim).map(|(owner, _)| owner), Some(2));
+		}
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::reserved_balance(&2), 43 * 2);
+	})
+}
+
+//5.测试 Merkle 根上链
+
+#[test]
+fn anchor_root_and_verify_inclusion_works() {
+	use crate::merkle::{hash_leaf, hash_node, MerkleProof};
+
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		//三个叶子的树：根 = H(1 || H(1 || a || b) || c)，c 落单直接升层
+		let algorithm = HashAlgorithm::Sha2_256;
+		let leaves = [[1u8; 32], [2u8; 32], [3u8; 32]];
+		let [a, b, c] = leaves.map(|leaf| hash_leaf(algorithm, &leaf));
+		let ab = hash_node(algorithm, &a, &b);
+		let root = Claim { algorithm, digest: hash_node(algorithm, &ab, &c) };
+
+		//根还没有上链
+		let proof = MerkleProof { leaf_index: 2, siblings: vec![ab] };
+		assert!(!PoeModule::verify_inclusion(root, leaves[2], proof.clone()));
+
+		assert_noop!(PoeModule::anchor_root(Origin::signed(1), root, 0), Error::<Test>::EmptyTree);
+		assert_ok!(PoeModule::anchor_root(Origin::signed(1), root, 3));
+		System::assert_last_event(crate::Event::RootAnchored(1, root, 3).into());
+
+		//根和普通存证一样锁定押金
+		assert_eq!(Proofs::<Test>::get(&root), Some((1, 1)));
+		assert_eq!(Balances::reserved_balance(&1), 43);
+		assert_eq!(PoeModule::anchored_roots(&root), Some(3));
+
+		assert!(PoeModule::verify_inclusion(root, leaves[2], proof.clone()));
+		let first = MerkleProof { leaf_index: 0, siblings: vec![b, c] };
+		assert!(PoeModule::verify_inclusion(root, leaves[0], first));
+
+		//叶子、位置不对，或者证明不完整
+		assert!(!PoeModule::verify_inclusion(root, leaves[1], proof.clone()));
+		let wrong_index = MerkleProof { leaf_index: 1, siblings: vec![ab] };
+		assert!(!PoeModule::verify_inclusion(root, leaves[2], wrong_index));
+		let missing = MerkleProof { leaf_index: 0, siblings: vec![b] };
+		assert!(!PoeModule::verify_inclusion(root, leaves[0], missing));
+
+		//普通存证不能当作 Merkle 根
+		let claim = claim_of(&[0, 1]);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim));
+		let single = MerkleProof { leaf_index: 0, siblings: vec![] };
+		assert!(!PoeModule::verify_inclusion(claim, [0u8; 32], single));
+
+		//撤销后不能再验证
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), root));
+		assert_eq!(PoeModule::anchored_roots(&root), None);
+		assert!(!PoeModule::verify_inclusion(root, leaves[2], proof));
+	})
+}
+
+//6.测试存储迁移
+
+#[test]
+fn migrate_v1_rekeys_claims_and_keeps_deposits() {
+	use frame_support::{
+		storage::migration::put_storage_value,
+		traits::{GetStorageVersion, ReservableCurrency, StorageVersion},
+		Blake2_128Concat, StorageHasher,
+	};
+
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<PoeModule>();
+
+		//按 v0 的格式写入旧存证：32字节的摘要、任意字节，以及同一内容的原文和摘要
+		let digest = [7u8; 32];
+		let raw = vec![1u8, 2, 3];
+		let content = vec![4u8, 5, 6];
+		let content_claim = Claim::from_content(HashAlgorithm::Blake2_256, &content);
+		let old_claims = [
+			(digest.to_vec(), 1u64),
+			(raw.clone(), 2u64),
+			(content.clone(), 1u64),
+			(content_claim.digest.to_vec(), 1u64),
+		];
+		for (old_claim, owner) in old_claims {
+			let key = Blake2_128Concat::hash(&old_claim.encode());
+			put_storage_value(b"PoeModule", b"Proofs", &key, (owner, 1u64));
+			put_storage_value(b"PoeModule", b"Deposits", &key, 20u64);
+			assert_ok!(Balances::reserve(&owner, 20));
+		}
+
+		crate::migrations::v1::migrate::<Test>();
+
+		//32字节的旧存证成为 Blake2-256 存证，押金保持锁定
+		let claim = Claim { algorithm: HashAlgorithm::Blake2_256, digest };
+		assert_eq!(Proofs::<Test>::get(&claim), Some((1, 1)));
+		assert_eq!(PoeModule::deposits(&claim), 20);
+
+		//其它旧存证按内容计算 Blake2-256 摘要，拥有者、区块和押金不变
+		let raw_claim = Claim::from_content(HashAlgorithm::Blake2_256, &raw);
+		assert_eq!(Proofs::<Test>::get(&raw_claim), Some((2, 1)));
+		assert_eq!(PoeModule::deposits(&raw_claim), 20);
+		assert_eq!(Balances::reserved_balance(&2), 20);
+
+		//同一内容的原文和摘要合并为一个存证，重复的押金释放
+		assert_eq!(Proofs::<Test>::get(&content_claim), Some((1, 1)));
+		assert_eq!(PoeModule::deposits(&content_claim), 20);
+		assert_eq!(Balances::reserved_balance(&1), 40);
+
+		assert_eq!(Proofs::<Test>::iter().count(), 3);
+		assert_eq!(PoeModule::on_chain_storage_version(), 1);
+	})
+}
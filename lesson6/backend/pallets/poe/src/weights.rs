@@ -15,36 +15,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Autogenerated weights for pallet_poe
+//! Weights for pallet_poe
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2022-10-10, STEPS: `20`, REPEAT: 10, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! HOSTNAME: `MacBookPro`, CPU: `<UNKNOWN>`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 1024
-
-// Executed Command:
-// ./target/debug/node-template
-// benchmark
-// pallet
-// --chain
-// dev
-// --execution
-// wasm
-// --wasm-execution
-// compiled
-// --pallet
-// pallet_poe
-// --extrinsic
-// *
-// --steps
-// 20
-// --repeat
-// 10
-// --json-file=raw.json
-// --output
-// ./pallets/poe/src/weights.rs
-// --template
-// .maintain/frame-weight-template.hbs
+//! These weights are hand estimates, not benchmark output. `create_claim`, `transfer_claim` and
+//! `revoke_claim` keep the base times measured for the old variable-length claims, with the storage
+//! accesses for deposits, metadata and anchored roots added by hand. `anchor_root` runs
+//! `create_claim` and one more write, so it reuses the `create_claim` base time with the
+//! `AnchoredRoots` write added. The other calls have estimated base times. `set_claim_metadata` is
+//! linear in the metadata length `b`, and the batch calls are linear in the number of claims `c`
+//! and their total metadata length `b`, with estimated per-claim and per-byte times; claims
+//! themselves are fixed-size digests, so they add no length term. The storage reads and writes
+//! follow the worst cases set up in `benchmarking.rs`. Replace this file with the output of
+//! `./target/release/node-template benchmark pallet --pallet pallet_poe --extrinsic '*'`, built
+//! with `--features runtime-benchmarks` and run on reference hardware, before relying on them.
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -55,70 +38,199 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for pallet_poe.
 pub trait WeightInfo {
-	fn create_claim(d: u32, ) -> Weight;
-	fn transfer_claim(d: u32, ) -> Weight;
-	fn revoke_claim(d: u32, ) -> Weight;
+	fn create_claim() -> Weight;
+	fn transfer_claim() -> Weight;
+	fn revoke_claim() -> Weight;
+	fn set_claim_metadata(b: u32, ) -> Weight;
+	fn create_claims(c: u32, ) -> Weight;
+	fn revoke_claims(c: u32, b: u32, ) -> Weight;
+	fn transfer_claims(c: u32, b: u32, ) -> Weight;
+	fn anchor_root() -> Weight;
 }
 
-/// Weights for pallet_poe using the Substrate node and recommended hardware.
+/// Hand-estimated weights for pallet_poe, see the module docs.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Storage: PoeModule Proofs (r:1 w:1)
-	/// The range of component `d` is `[0, 512]`.
-	fn create_claim(d: u32, ) -> Weight {
+	// Storage: PoeModule Deposits (r:0 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn create_claim() -> Weight {
 		(738_316_000 as Weight)
-			// Standard Error: 88_000
-			.saturating_add((59_000 as Weight).saturating_mul(d as Weight))
-			.saturating_add(T::DbWeight::get().reads(1 as Weight))
-			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
 	}
 	// Storage: PoeModule Proofs (r:1 w:1)
-	/// The range of component `d` is `[0, 512]`.
-	fn transfer_claim(d: u32, ) -> Weight {
+	// Storage: PoeModule Deposits (r:1 w:1)
+	// Storage: PoeModule ClaimMetadataOf (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn transfer_claim() -> Weight {
 		(723_545_000 as Weight)
-			// Standard Error: 73_000
-			.saturating_add((138_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: PoeModule Deposits (r:1 w:1)
+	// Storage: PoeModule ClaimMetadataOf (r:1 w:1)
+	// Storage: PoeModule AnchoredRoots (r:0 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn revoke_claim() -> Weight {
+		(686_984_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:0)
+	// Storage: PoeModule ClaimMetadataOf (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[0, 544]`.
+	fn set_claim_metadata(b: u32, ) -> Weight {
+		(700_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: System Account (r:1 w:1)
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: PoeModule Deposits (r:0 w:1)
+	/// The range of component `c` is `[1, 500]`.
+	fn create_claims(c: u32, ) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((700_000_000 as Weight).saturating_mul(c as Weight))
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(c as Weight)))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
 	}
+	// Storage: System Account (r:1 w:1)
 	// Storage: PoeModule Proofs (r:1 w:1)
-	/// The range of component `d` is `[0, 512]`.
-	fn revoke_claim(d: u32, ) -> Weight {
-		(686_984_000 as Weight)
-			// Standard Error: 60_000
-			.saturating_add((191_000 as Weight).saturating_mul(d as Weight))
+	// Storage: PoeModule Deposits (r:1 w:1)
+	// Storage: PoeModule ClaimMetadataOf (r:1 w:1)
+	// Storage: PoeModule AnchoredRoots (r:0 w:1)
+	/// The range of component `c` is `[1, 500]`.
+	/// The range of component `b` is `[0, 272000]`.
+	fn revoke_claims(c: u32, b: u32, ) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((650_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((1_000 as Weight).saturating_mul(b as Weight))
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(c as Weight)))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: System Account (r:2 w:2)
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: PoeModule Deposits (r:1 w:1)
+	// Storage: PoeModule ClaimMetadataOf (r:1 w:1)
+	/// The range of component `c` is `[1, 500]`.
+	/// The range of component `b` is `[0, 272000]`.
+	fn transfer_claims(c: u32, b: u32, ) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((690_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((1_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: PoeModule Deposits (r:0 w:1)
+	// Storage: PoeModule AnchoredRoots (r:0 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn anchor_root() -> Weight {
+		(738_316_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
 	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
 	// Storage: PoeModule Proofs (r:1 w:1)
-	/// The range of component `d` is `[0, 512]`.
-	fn create_claim(d: u32, ) -> Weight {
+	// Storage: PoeModule Deposits (r:0 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn create_claim() -> Weight {
 		(738_316_000 as Weight)
-			// Standard Error: 88_000
-			.saturating_add((59_000 as Weight).saturating_mul(d as Weight))
-			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 	}
 	// Storage: PoeModule Proofs (r:1 w:1)
-	/// The range of component `d` is `[0, 512]`.
-	fn transfer_claim(d: u32, ) -> Weight {
+	// Storage: PoeModule Deposits (r:1 w:1)
+	// Storage: PoeModule ClaimMetadataOf (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn transfer_claim() -> Weight {
 		(723_545_000 as Weight)
-			// Standard Error: 73_000
-			.saturating_add((138_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: PoeModule Deposits (r:1 w:1)
+	// Storage: PoeModule ClaimMetadataOf (r:1 w:1)
+	// Storage: PoeModule AnchoredRoots (r:0 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn revoke_claim() -> Weight {
+		(686_984_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:0)
+	// Storage: PoeModule ClaimMetadataOf (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[0, 544]`.
+	fn set_claim_metadata(b: u32, ) -> Weight {
+		(700_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: System Account (r:1 w:1)
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: PoeModule Deposits (r:0 w:1)
+	/// The range of component `c` is `[1, 500]`.
+	fn create_claims(c: u32, ) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((700_000_000 as Weight).saturating_mul(c as Weight))
 			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(c as Weight)))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
 	}
+	// Storage: System Account (r:1 w:1)
 	// Storage: PoeModule Proofs (r:1 w:1)
-	/// The range of component `d` is `[0, 512]`.
-	fn revoke_claim(d: u32, ) -> Weight {
-		(686_984_000 as Weight)
-			// Standard Error: 60_000
-			.saturating_add((191_000 as Weight).saturating_mul(d as Weight))
+	// Storage: PoeModule Deposits (r:1 w:1)
+	// Storage: PoeModule ClaimMetadataOf (r:1 w:1)
+	// Storage: PoeModule AnchoredRoots (r:0 w:1)
+	/// The range of component `c` is `[1, 500]`.
+	/// The range of component `b` is `[0, 272000]`.
+	fn revoke_claims(c: u32, b: u32, ) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((650_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((1_000 as Weight).saturating_mul(b as Weight))
 			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(c as Weight)))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: System Account (r:2 w:2)
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: PoeModule Deposits (r:1 w:1)
+	// Storage: PoeModule ClaimMetadataOf (r:1 w:1)
+	/// The range of component `c` is `[1, 500]`.
+	/// The range of component `b` is `[0, 272000]`.
+	fn transfer_claims(c: u32, b: u32, ) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((690_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((1_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: PoeModule Deposits (r:0 w:1)
+	// Storage: PoeModule AnchoredRoots (r:0 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn anchor_root() -> Weight {
+		(738_316_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
 	}
 }
\ No newline at end of file
@@ -319,8 +319,15 @@ impl pallet_sudo::Config for Runtime {
 // }
 
 impl pallet_poe::Config for Runtime {
-	type MaxClaimLength = ConstU32<512>;
 	type Event = Event;
+	type Currency = Balances;
+	type ClaimDepositBase = ConstU128<1_000>;
+	type ClaimDepositPerByte = ConstU128<10>;
+	type MaxDescriptionLength = ConstU32<256>;
+	type MaxContentTypeLength = ConstU32<64>;
+	type MaxUriLength = ConstU32<256>;
+	type MetadataDepositPerByte = ConstU128<10>;
+	type MaxClaimsPerBatch = ConstU32<500>;
 	type WeightInfo = pallet_poe::weights::SubstrateWeight<Runtime>;
 }
 
@@ -532,6 +539,23 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_poe_rpc_runtime_api::PoeApi<Block> for Runtime {
+		fn create_claim_from_content(
+			algorithm: pallet_poe::HashAlgorithm,
+			content: Vec<u8>,
+		) -> pallet_poe::Claim {
+			PoeModule::create_claim_from_content(algorithm, content)
+		}
+
+		fn verify_inclusion(
+			root: pallet_poe::Claim,
+			leaf: [u8; 32],
+			proof: pallet_poe::MerkleProof,
+		) -> bool {
+			PoeModule::verify_inclusion(root, leaf, proof)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (